@@ -1,15 +1,16 @@
 use std::collections::HashSet;
 
 use kismet::compile;
-use kismet::hir::{Exec, SymbolTable};
+use kismet::hir::{Analysis, Context, Exec};
 use kismet::parse;
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
 
-use crate::Print;
+use crate::{dist, Print};
 
 pub struct State {
     pub print: HashSet<Print>,
+    pub seed: Option<u64>,
 }
 
 pub fn run(state: &mut State) {
@@ -17,12 +18,16 @@ pub fn run(state: &mut State) {
         "\
         Hello, I am Kismet <3\n\
         Input a roll and press ENTER.\n\
+        Analyze a roll with ':dist <roll>'.\n\
         Exit with 'exit' or CTRL-D.\
         "
     );
 
     let mut rl = Editor::<()>::new();
-    let mut i = SymbolTable::default();
+    let mut i = match state.seed {
+        Some(seed) => Context::seeded(seed),
+        None => Context::default(),
+    };
     loop {
         let readline = rl.readline("> ");
         match readline {
@@ -31,6 +36,16 @@ pub fn run(state: &mut State) {
                 if line == "exit" {
                     println!("Goodbye <3");
                     break;
+                } else if let Some(line) = line.strip_prefix(":dist ") {
+                    dist(state, &mut i, line, true, true);
+                } else if state.print.contains(&Print::Dist) || state.print.contains(&Print::Hist) {
+                    dist(
+                        state,
+                        &mut i,
+                        &line,
+                        state.print.contains(&Print::Dist),
+                        state.print.contains(&Print::Hist),
+                    );
                 } else {
                     match parse(&line) {
                         Ok(x) => {
@@ -41,9 +56,8 @@ pub fn run(state: &mut State) {
                                 println!("{}", x)
                             }
                             match compile(x) {
-                                Ok(x) => match x.exec(i.clone()) {
-                                    Ok((i_, val)) => {
-                                        i = i_;
+                                Ok(x) => match x.exec(&mut i) {
+                                    Ok(val) => {
                                         if state.print.contains(&Print::Output) {
                                             println!("{}", val)
                                         }
@@ -81,3 +95,40 @@ pub fn run(state: &mut State) {
         }
     }
 }
+
+fn dist(state: &State, i: &mut Context, line: &str, table: bool, hist: bool) {
+    let mut analysis = Analysis::from(&*i);
+    match parse(line) {
+        Ok(x) => match compile(x) {
+            Ok(x) => match x.exec(&mut analysis) {
+                Ok(val) => {
+                    i.sym = analysis.sym;
+                    i.stack = analysis.stack;
+                    if state.print.contains(&Print::Output) {
+                        if hist {
+                            println!("{}", dist::hist(&val))
+                        }
+                        if table {
+                            println!("{}", dist::table(&val))
+                        }
+                    }
+                }
+                Err(x) => {
+                    if state.print.contains(&Print::Error) {
+                        println!("Analysis Error: {:#?}", x)
+                    }
+                }
+            },
+            Err(x) => {
+                if state.print.contains(&Print::Error) {
+                    println!("Compile Error: {:#?}", x)
+                }
+            }
+        },
+        Err(x) => {
+            if state.print.contains(&Print::Error) {
+                println!("Parse Error: {:#?}", x)
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use kismet::hir::Dist;
+
+const WIDTH: usize = 50;
+
+pub fn table(dist: &Dist) -> String {
+    let width = value_width(dist).max(5);
+    let mut lines = vec![format!(
+        "{:>width$} {:>9} {:>9} {:>9}",
+        "value",
+        "prob",
+        "at least",
+        "at most",
+        width = width
+    )];
+    let mut at_most = 0.;
+    for (val, p) in dist.iter() {
+        let at_least = 1. - at_most;
+        at_most += p;
+        lines.push(format!(
+            "{:>width$} {:>9} {:>9} {:>9}",
+            val.to_string(),
+            percent(*p),
+            percent(at_least),
+            percent(at_most),
+            width = width
+        ));
+    }
+    lines.join("\n")
+}
+
+pub fn hist(dist: &Dist) -> String {
+    let width = value_width(dist);
+    let max = dist.iter().map(|x| x.1).fold(0., f64::max);
+    dist.iter()
+        .map(|(val, p)| {
+            let bar = match max > 0. {
+                true => (p / max * WIDTH as f64).round() as usize,
+                false => 0,
+            };
+            format!(
+                "{:>width$} | {:<bar_width$} {:>9}",
+                val.to_string(),
+                "#".repeat(bar),
+                percent(*p),
+                width = width,
+                bar_width = WIDTH
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn value_width(dist: &Dist) -> usize {
+    dist.iter()
+        .map(|x| x.0.to_string().len())
+        .max()
+        .unwrap_or(0)
+}
+
+fn percent(p: f64) -> String {
+    format!("{:.2}%", (p * 100.).clamp(0., 100.))
+}
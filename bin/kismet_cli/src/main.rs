@@ -3,12 +3,15 @@ use std::collections::HashSet;
 use clap::{ArgEnum, Parser};
 
 mod cli;
+mod dist;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about)]
 pub struct Args {
     #[clap(multiple = true, long, action, help = "Default: [output, error]\n ")]
     print: Vec<Print>,
+    #[clap(long, help = "Seed for reproducible rolls")]
+    seed: Option<u64>,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq, ArgEnum)]
@@ -18,6 +21,8 @@ pub enum Print {
     Loopback,
     Output,
     Error,
+    Dist,
+    Hist,
 }
 
 fn main() {
@@ -33,6 +38,9 @@ fn main() {
         println!("{:?}", args);
     }
 
-    let mut state = cli::State { print };
+    let mut state = cli::State {
+        print,
+        seed: args.seed,
+    };
     cli::run(&mut state);
 }
@@ -0,0 +1,46 @@
+use kismet::{analyze, compile, hir::Dist, parse};
+
+#[path = "../src/dist.rs"]
+mod dist;
+
+fn analysis(input: &str) -> Dist {
+    analyze(&compile(parse(input).unwrap()).unwrap()).unwrap()
+}
+
+#[test]
+fn table() {
+    assert_eq!(
+        dist::table(&analysis(r###"d4"###)),
+        [
+            "value      prob  at least   at most",
+            "    1    25.00%   100.00%    25.00%",
+            "    2    25.00%    75.00%    50.00%",
+            "    3    25.00%    50.00%    75.00%",
+            "    4    25.00%    25.00%   100.00%",
+        ]
+        .join("\n")
+    );
+    assert_eq!(
+        dist::table(&analysis(r###"d2 * 50"###)).lines().last(),
+        Some("  100    50.00%    50.00%   100.00%")
+    );
+}
+
+#[test]
+fn hist() {
+    let full = "#".repeat(50);
+    let half = "#".repeat(25);
+    assert_eq!(
+        dist::hist(&analysis(r###"2d2"###)),
+        [
+            format!("2 | {:<50}    25.00%", half),
+            format!("3 | {}    50.00%", full),
+            format!("4 | {:<50}    25.00%", half),
+        ]
+        .join("\n")
+    );
+    assert_eq!(
+        dist::hist(&analysis(r###"7"###)),
+        format!("7 | {}   100.00%", full)
+    );
+}
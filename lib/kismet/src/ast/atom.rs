@@ -1,9 +1,11 @@
-use std::{fmt, ops::Deref};
+use std::{fmt, ops::Deref, sync::Arc};
 
 use indexmap::IndexMap;
 
 use crate::{
-    hir::{self, Action, Block, Collection, DictItem, Instruction, Primitive, Value},
+    hir::{
+        self, Action, Block, Collection, Comprehension, DictItem, Instruction, Primitive, Value,
+    },
     types::{fmt_float, Float, Integer, Node},
 };
 
@@ -53,7 +55,7 @@ impl fmt::Display for Id {
 
 impl fmt::Display for Atom {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &*self {
+        match self {
             Self::Id(val) => write!(f, "{}", val),
             Self::Integer(val) => write!(f, "{}", val),
             Self::Float(val) => fmt_float(f, val),
@@ -84,9 +86,17 @@ impl fmt::Display for Atom {
     }
 }
 
+fn comp_iter(val: Vec<Node<CompIter>>) -> Result<Vec<Node<hir::CompIter>>, Error> {
+    val.into_iter()
+        .map(|x| Ok(Node::<hir::CompIter>::try_convert_from(x)?))
+        .collect()
+}
+
 impl TryFrom<Atom> for Instruction {
     type Error = Error;
 
+    // The folds below accumulate unfoldable items in `Err` rather than short-circuit
+    #[allow(clippy::manual_try_fold)]
     fn try_from(val: Atom) -> Result<Self, Self::Error> {
         type ListValueResult =
             Result<Result<Vec<Value>, Vec<Node<hir::ListItem<Instruction>>>>, Error>;
@@ -272,9 +282,25 @@ impl TryFrom<Atom> for Instruction {
                     Err(x) => Ok(Instruction::Action(Action::DictDisplay(x))),
                 }
             }
-            Atom::Generator { val: _, iter: _ } => todo!(),
-            Atom::ListComprehension { val: _, iter: _ } => todo!(),
-            Atom::DictComprehension { val: _, iter: _ } => todo!(),
+            Atom::Generator { val, iter } => Ok(Instruction::Generator(Arc::new(Comprehension {
+                val: Node::try_from(val)?,
+                iter: comp_iter(iter)?,
+                captures: vec![],
+            }))),
+            Atom::ListComprehension { val, iter } => {
+                Ok(Instruction::ListComprehension(Arc::new(Comprehension {
+                    val: Node::try_from(val)?,
+                    iter: comp_iter(iter)?,
+                    captures: vec![],
+                })))
+            }
+            Atom::DictComprehension { val, iter } => {
+                Ok(Instruction::DictComprehension(Arc::new(Comprehension {
+                    val: Node::try_from(val)?,
+                    iter: comp_iter(iter)?,
+                    captures: vec![],
+                })))
+            }
             Atom::Block(x) => Ok(Instruction::Block(Block::try_from(x.iter())?)),
         }
     }
@@ -1,8 +1,11 @@
 use std::fmt;
 
-use crate::types::Node;
+use crate::{
+    hir::{self, Block, Instruction, Pattern},
+    types::Node,
+};
 
-use super::{Expr, ExprEnclosure, Id, Match, Target};
+use super::{Error, Expr, ExprEnclosure, Id, Match, Target};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Branch {
@@ -89,3 +92,70 @@ impl fmt::Display for MatchArm {
         write!(f, "{} => {}", self.tar, self.block)
     }
 }
+
+impl TryFrom<Branch> for Instruction {
+    type Error = Error;
+
+    fn try_from(val: Branch) -> Result<Self, Self::Error> {
+        match val {
+            Branch::If {
+                val,
+                t_block,
+                f_block,
+            } => {
+                let val = Node::<Instruction>::try_convert_from(val)?;
+                let t_block = Node::<Block>::try_convert_from(t_block)?;
+                let f_block = Node::<Block>::try_convert_from(f_block)?;
+                match &*val.data {
+                    Instruction::Value(x) => match x.truthy() {
+                        true => Ok(Instruction::Block(*t_block.data)),
+                        false => Ok(Instruction::Block(*f_block.data)),
+                    },
+                    _ => Ok(Instruction::Branch(hir::Branch::If {
+                        val,
+                        t_block,
+                        f_block,
+                    })),
+                }
+            }
+            Branch::Match { val, arms } => Ok(Instruction::Branch(hir::Branch::Match {
+                val: Node::<Instruction>::try_convert_from(val)?,
+                arms: arms
+                    .into_iter()
+                    .map(|x| {
+                        let x = *x.data;
+                        Ok(hir::MatchArm {
+                            tar: Node::<Pattern>::try_convert_from::<Match, _>(x.tar)?,
+                            block: Node::<Block>::try_convert_from(x.block)?,
+                        })
+                    })
+                    .collect::<Result<_, Error>>()?,
+            })),
+        }
+    }
+}
+
+impl TryFrom<Loop> for Instruction {
+    type Error = Error;
+
+    fn try_from(val: Loop) -> Result<Self, Self::Error> {
+        let kind = match *val.val.data {
+            LoopKind::For { tar, val, block } => hir::LoopKind::For {
+                tar: Node::<Pattern>::try_convert_from::<Target, _>(tar)?,
+                val: Node::<Instruction>::try_convert_from(val)?,
+                block: Node::<Block>::try_convert_from(block)?,
+            },
+            LoopKind::While { val, block } => hir::LoopKind::While {
+                val: Node::<Instruction>::try_convert_from(val)?,
+                block: Node::<Block>::try_convert_from(block)?,
+            },
+            LoopKind::Loop { block } => hir::LoopKind::Loop {
+                block: Node::<Block>::try_convert_from(block)?,
+            },
+        };
+        Ok(Instruction::Loop(hir::Loop {
+            id: val.id.map(|x| *x.data),
+            kind,
+        }))
+    }
+}
@@ -1,7 +1,10 @@
 use std::fmt;
 
-use super::{Expr, Target};
-use crate::types::Node;
+use super::{Error, Expr, Target};
+use crate::{
+    hir::{self, Instruction, Pattern},
+    types::Node,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum CompIter {
@@ -14,7 +17,7 @@ pub enum CompIter {
 
 impl fmt::Display for CompIter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &*self {
+        match self {
             Self::For { target, val } => {
                 write!(f, "for {} in {}", target, val)
             }
@@ -22,3 +25,19 @@ impl fmt::Display for CompIter {
         }
     }
 }
+
+impl TryFrom<CompIter> for hir::CompIter {
+    type Error = Error;
+
+    fn try_from(val: CompIter) -> Result<Self, Self::Error> {
+        match val {
+            CompIter::For { target, val } => Ok(hir::CompIter::For {
+                tar: Node::<Pattern>::try_convert_from::<Target, _>(target)?,
+                val: Node::<Instruction>::try_convert_from(val)?,
+            }),
+            CompIter::If(val) => Ok(hir::CompIter::If(Node::<Instruction>::try_convert_from(
+                val,
+            )?)),
+        }
+    }
+}
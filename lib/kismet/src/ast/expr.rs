@@ -1,12 +1,12 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use crate::{
-    hir::Instruction,
+    hir::{Bind, Block, Function, Instruction, Param, Pattern},
     types::{CommaList, Node},
 };
 
 use super::{
-    Atom, Branch, Error, ExprEnclosure, Id, Loop, Op, Primary, Stmt, Target, TargetExpr,
+    assign, Atom, Branch, Error, ExprEnclosure, Id, Loop, Op, Primary, Stmt, Target, TargetExpr,
     TargetListItem,
 };
 
@@ -67,11 +67,38 @@ impl TryFrom<Expr> for Instruction {
 
     fn try_from(val: Expr) -> Result<Self, Self::Error> {
         match val {
-            Expr::Stmt(_) => todo!(),
-            Expr::Assign(_, _) => todo!(),
-            Expr::Function { args: _, block: _ } => todo!(),
-            Expr::Branch(_) => todo!(),
-            Expr::Loop(_) => todo!(),
+            Expr::Stmt(x) => Instruction::try_from(x),
+            Expr::Assign(lhs, rhs) => assign(Bind::Declare, lhs, rhs),
+            Expr::Function { args, block } => {
+                let len = args.data.len();
+                let params = args
+                    .data
+                    .0
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, x)| match *x.data {
+                        TargetListItem::Target(TargetExpr::TargetExpr(tar, val)) => {
+                            Ok(Param::Target(
+                                Pattern::try_from(*tar.data)?,
+                                Some(Node::<Instruction>::try_convert_from(val)?),
+                            ))
+                        }
+                        TargetListItem::Target(x) => Ok(Param::Target(Pattern::try_from(x)?, None)),
+                        TargetListItem::Spread(x) if idx + 1 == len => {
+                            Ok(Param::Spread(Pattern::try_from(*x.data)?))
+                        }
+                        TargetListItem::Spread(_) => Err(Error::InvalidOp),
+                    })
+                    .collect::<Result<_, Error>>()?;
+                Ok(Instruction::Function(Arc::new(Function {
+                    params,
+                    block: Node::<Block>::try_convert_from(block)?,
+                    captures: vec![],
+                    name: None,
+                })))
+            }
+            Expr::Branch(x) => Instruction::try_from(x),
+            Expr::Loop(x) => Instruction::try_from(x),
             Expr::Op(x) => Instruction::try_from(x),
             Expr::Primary(x) => Instruction::try_from(x),
         }
@@ -1,11 +1,11 @@
 use std::fmt;
 
 use crate::{
-    hir::{Instruction, Primitive, Value},
-    types::{Float, Integer, Node, UInteger},
+    hir::{self, Collection, Dice, Instruction, Primitive, RangeExpr, Value},
+    types::{Node, Span},
 };
 
-use super::{Atom, Error, Expr, Range};
+use super::{Atom, Error, Expr, Primary, Range};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Op {
@@ -24,7 +24,24 @@ pub enum Op {
     Arith(Node<Expr>, Node<OpArith>, Node<Expr>),
     Unary(Node<OpArith>, Node<Expr>),
     Coefficient(Node<Atom>, Node<Expr>),
-    Die(Node<Atom>),
+    Die(Node<Atom>, Vec<Node<DieMod>>),
+    Fudge(Span, Vec<Node<DieMod>>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DieMod {
+    Keep(Node<OpKeep>, Option<Node<Atom>>),
+    Explode(Node<OpExplode>, Option<Node<DieCompare>>),
+    Reroll(Node<OpReroll>, Option<Node<DieCompare>>),
+    Success(Node<DieCompare>),
+    Failure(Node<DieCompare>),
+    Crit(Node<OpCrit>, Option<Node<DieCompare>>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DieCompare {
+    pub op: Option<Node<OpEqs>>,
+    pub val: Node<Atom>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -43,6 +60,33 @@ pub enum OpRange {
     RANGEI,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OpKeep {
+    KH,
+    KL,
+    DH,
+    DL,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OpExplode {
+    EXPLODE,
+    COMPOUND,
+    PENETRATE,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OpReroll {
+    REROLL,
+    ONCE,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OpCrit {
+    SUCCESS,
+    FAILURE,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum OpArith {
     ADD,
@@ -54,6 +98,19 @@ pub enum OpArith {
     POW,
 }
 
+impl OpEqs {
+    pub fn compare<T: PartialOrd>(&self, lhs: &T, rhs: &T) -> bool {
+        match self {
+            Self::EQ => lhs == rhs,
+            Self::NE => lhs != rhs,
+            Self::LT => lhs < rhs,
+            Self::LE => lhs <= rhs,
+            Self::GT => lhs > rhs,
+            Self::GE => lhs >= rhs,
+        }
+    }
+}
+
 impl OpArith {
     pub fn space(&self) -> &'static str {
         match self {
@@ -91,10 +148,37 @@ impl fmt::Display for Op {
             }
             Self::Unary(lhs, val) => write!(f, "{}{}", lhs, val),
             Self::Coefficient(lhs, rhs) => write!(f, "{}{}", lhs, rhs),
-            Self::Die(val) => match *val.data {
-                Atom::Id(_) => write!(f, "d({})", val),
-                _ => write!(f, "d{}", val),
+            Self::Die(val, mods) => match *val.data {
+                Atom::Id(_) => write!(f, "d({}){}", val, Node::join(mods, "")),
+                _ => write!(f, "d{}{}", val, Node::join(mods, "")),
             },
+            Self::Fudge(_, mods) => write!(f, "dF{}", Node::join(mods, "")),
+        }
+    }
+}
+
+impl fmt::Display for DieMod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keep(op, Some(val)) => write!(f, "{}{}", op, val),
+            Self::Keep(op, None) => write!(f, "{}", op),
+            Self::Explode(op, Some(val)) => write!(f, "{}{}", op, val),
+            Self::Explode(op, None) => write!(f, "{}", op),
+            Self::Reroll(op, Some(val)) => write!(f, "{}{}", op, val),
+            Self::Reroll(op, None) => write!(f, "{}", op),
+            Self::Success(val) => write!(f, "s{}", val),
+            Self::Failure(val) => write!(f, "f{}", val),
+            Self::Crit(op, Some(val)) => write!(f, "{}{}", op, val),
+            Self::Crit(op, None) => write!(f, "{}", op),
+        }
+    }
+}
+
+impl fmt::Display for DieCompare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.op {
+            Some(op) => write!(f, "{}{}", op, self.val),
+            None => write!(f, "{}", self.val),
         }
     }
 }
@@ -121,6 +205,45 @@ impl fmt::Display for OpRange {
     }
 }
 
+impl fmt::Display for OpKeep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KH => write!(f, "kh"),
+            Self::KL => write!(f, "kl"),
+            Self::DH => write!(f, "dh"),
+            Self::DL => write!(f, "dl"),
+        }
+    }
+}
+
+impl fmt::Display for OpExplode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EXPLODE => write!(f, "!"),
+            Self::COMPOUND => write!(f, "!!"),
+            Self::PENETRATE => write!(f, "!p"),
+        }
+    }
+}
+
+impl fmt::Display for OpReroll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::REROLL => write!(f, "r"),
+            Self::ONCE => write!(f, "ro"),
+        }
+    }
+}
+
+impl fmt::Display for OpCrit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SUCCESS => write!(f, "cs"),
+            Self::FAILURE => write!(f, "cf"),
+        }
+    }
+}
+
 impl fmt::Display for OpArith {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -139,124 +262,258 @@ impl TryFrom<Op> for Instruction {
     type Error = Error;
 
     fn try_from(val: Op) -> Result<Self, Self::Error> {
-        fn arith_float(lhs: Float, op: OpArith, rhs: Float) -> Result<Instruction, Error> {
-            Ok(Instruction::Value(Value::Primitive(Primitive::Float(
-                match op {
-                    OpArith::ADD => lhs + rhs,
-                    OpArith::SUB => lhs - rhs,
-                    OpArith::MUL => lhs * rhs,
-                    OpArith::DIV => lhs / rhs,
-                    OpArith::IDIV => lhs.rem_euclid(rhs),
-                    OpArith::MOD => lhs % rhs,
-                    OpArith::POW => lhs.powf(rhs),
-                },
-            ))))
+        fn fudge(span: Span) -> Node<Instruction> {
+            Node::new(
+                span,
+                Instruction::Value(Value::Collection(Collection::List(
+                    (-1..=1)
+                        .map(|x| Value::Primitive(Primitive::Integer(x)))
+                        .collect(),
+                ))),
+            )
         }
 
-        fn arith_int(lhs: Integer, op: OpArith, rhs: Integer) -> Result<Instruction, Error> {
-            match match op {
-                OpArith::ADD => lhs.checked_add(rhs),
-                OpArith::SUB => lhs.checked_sub(rhs),
-                OpArith::MUL => lhs.checked_mul(rhs),
-                OpArith::DIV => return arith_float(lhs as Float, op, rhs as Float),
-                OpArith::IDIV => lhs.checked_div(rhs),
-                OpArith::MOD => lhs.checked_rem(rhs),
-                OpArith::POW => match UInteger::try_from(rhs) {
-                    Ok(rhs) => lhs.checked_pow(rhs),
-                    Err(_) => None,
-                },
-            } {
-                Some(x) => Ok(Instruction::Value(Value::Primitive(Primitive::Integer(x)))),
-                None => arith_float(lhs as Float, op, rhs as Float),
-            }
+        fn dice(count: Option<Node<Atom>>, val: Op) -> Result<Instruction, Error> {
+            let notation = match &count {
+                Some(count) => format!("{}{}", count, val),
+                None => val.to_string(),
+            };
+            let (sides, mods) = match val {
+                Op::Die(sides, mods) => (Node::<Instruction>::try_convert_from(sides)?, mods),
+                Op::Fudge(span, mods) => (fudge(span), mods),
+                _ => return Err(Error::InvalidOp),
+            };
+            let count = match count {
+                Some(count) => Node::<Instruction>::try_convert_from(count)?,
+                None => Node::new(
+                    sides.span.start..sides.span.start,
+                    Instruction::Value(Value::Primitive(Primitive::Integer(1))),
+                ),
+            };
+            Ok(Instruction::Dice(Dice {
+                notation,
+                count,
+                sides,
+                mods: die_mods(mods)?,
+            }))
+        }
+
+        fn die_mods(val: Vec<Node<DieMod>>) -> Result<Vec<Node<hir::DieMod>>, Error> {
+            val.into_iter()
+                .map(Node::<hir::DieMod>::try_convert_from)
+                .collect::<Result<_, _>>()
+                .map_err(Error::Node)
         }
 
         match val {
-            Op::And(_, _) => todo!(),
-            Op::Or(_, _) => todo!(),
-            Op::Not(_) => todo!(),
-            Op::CompareBound {
-                l_val: _,
-                l_op: _,
-                val: _,
-                r_op: _,
-                r_val: _,
-            } => todo!(),
-            Op::Compare(_, _, _) => todo!(),
-            Op::Range(_) => todo!(),
-            Op::Arith(lhs, op, rhs) => {
+            Op::And(lhs, rhs) => {
                 let lhs = Node::<Instruction>::try_convert_from(lhs)?;
                 let rhs = Node::<Instruction>::try_convert_from(rhs)?;
-                match (*lhs.data, *rhs.data) {
-                    (Instruction::Value(lhs), Instruction::Value(rhs)) => match (lhs, rhs) {
-                        (
-                            Value::Primitive(Primitive::Integer(lhs)),
-                            Value::Primitive(Primitive::Integer(rhs)),
-                        ) => arith_int(lhs, *op, rhs),
-                        (
-                            Value::Primitive(Primitive::Float(lhs)),
-                            Value::Primitive(Primitive::Float(rhs)),
-                        ) => arith_float(lhs, *op, rhs),
-                        (
-                            Value::Primitive(Primitive::Integer(lhs)),
-                            Value::Primitive(Primitive::Float(rhs)),
-                        ) => match (*op, lhs) {
-                            (OpArith::POW, 2) => Ok(Instruction::Value(Value::Primitive(
-                                Primitive::Float(rhs.exp2()),
-                            ))),
-                            _ => arith_float(lhs as Float, *op, rhs),
-                        },
-                        (
-                            Value::Primitive(Primitive::Float(lhs)),
-                            Value::Primitive(Primitive::Integer(rhs)),
-                        ) => match *op {
-                            OpArith::POW => Ok(Instruction::Value(Value::Primitive(
-                                Primitive::Float(lhs.powi(rhs)),
+                match *lhs.data {
+                    Instruction::Value(lhs) => match lhs.truthy() {
+                        true => Ok(*rhs.data),
+                        false => Ok(Instruction::Value(lhs)),
+                    },
+                    lhs_data => Ok(Instruction::And(Node::new(lhs.span, lhs_data), rhs)),
+                }
+            }
+            Op::Or(lhs, rhs) => {
+                let lhs = Node::<Instruction>::try_convert_from(lhs)?;
+                let rhs = Node::<Instruction>::try_convert_from(rhs)?;
+                match *lhs.data {
+                    Instruction::Value(lhs) => match lhs.truthy() {
+                        true => Ok(Instruction::Value(lhs)),
+                        false => Ok(*rhs.data),
+                    },
+                    lhs_data => Ok(Instruction::Or(Node::new(lhs.span, lhs_data), rhs)),
+                }
+            }
+            Op::Not(val) => {
+                let val = Node::<Instruction>::try_convert_from(val)?;
+                match *val.data {
+                    Instruction::Value(val) => Ok(Instruction::Value(Value::Primitive(
+                        Primitive::Boolean(!val.truthy()),
+                    ))),
+                    val_data => Ok(Instruction::Not(Node::new(val.span, val_data))),
+                }
+            }
+            Op::CompareBound {
+                l_val,
+                l_op,
+                val,
+                r_op,
+                r_val,
+            } => {
+                let l_val = Node::<Instruction>::try_convert_from(l_val)?;
+                let val = Node::<Instruction>::try_convert_from(val)?;
+                let r_val = Node::<Instruction>::try_convert_from(r_val)?;
+                match (*l_val.data, *val.data, *r_val.data) {
+                    (
+                        Instruction::Value(l_val),
+                        Instruction::Value(val),
+                        Instruction::Value(r_val),
+                    ) => match l_val.compare(*l_op, &val) {
+                        Ok(false) => Ok(Instruction::Value(Value::Primitive(Primitive::Boolean(
+                            false,
+                        )))),
+                        Ok(true) => match val.compare(*r_op, &r_val) {
+                            Ok(res) => Ok(Instruction::Value(Value::Primitive(
+                                Primitive::Boolean(res),
                             ))),
-                            _ => arith_float(lhs, *op, rhs as Float),
+                            Err(_) => Err(Error::TypeMismatch),
                         },
-                        (
-                            Value::Primitive(Primitive::String(lhs)),
-                            Value::Primitive(Primitive::String(rhs)),
-                        ) => match *op {
-                            OpArith::ADD => Ok(Instruction::Value(Value::Primitive(
-                                Primitive::String(lhs + &rhs),
+                        Err(_) => Err(Error::TypeMismatch),
+                    },
+                    (l_data, val_data, r_data) => Ok(Instruction::CompareBound {
+                        l_val: Node::new(l_val.span, l_data),
+                        l_op: *l_op,
+                        val: Node::new(val.span, val_data),
+                        r_op: *r_op,
+                        r_val: Node::new(r_val.span, r_data),
+                    }),
+                }
+            }
+            Op::Compare(lhs, op, rhs) => {
+                let lhs = Node::<Instruction>::try_convert_from(lhs)?;
+                let rhs = Node::<Instruction>::try_convert_from(rhs)?;
+                match (*lhs.data, *rhs.data) {
+                    (Instruction::Value(lhs), Instruction::Value(rhs)) => {
+                        match lhs.compare(*op, &rhs) {
+                            Ok(val) => Ok(Instruction::Value(Value::Primitive(
+                                Primitive::Boolean(val),
                             ))),
-                            _ => Err(Error::InvalidOp),
-                        },
-                        _ => Err(Error::TypeMismatch),
+                            Err(_) => Err(Error::TypeMismatch),
+                        }
+                    }
+                    (lhs_data, rhs_data) => Ok(Instruction::Compare(
+                        Node::new(lhs.span, lhs_data),
+                        *op,
+                        Node::new(rhs.span, rhs_data),
+                    )),
+                }
+            }
+            Op::Range(val) => {
+                let (start, end, inclusive) = match val {
+                    Range::Range { start, end } => (Some(start), Some(end), false),
+                    Range::RangeFrom { start } => (Some(start), None, false),
+                    Range::RangeTo { end } => (None, Some(end), false),
+                    Range::RangeFull => (None, None, false),
+                    Range::RangeI { start, end } => (Some(start), Some(end), true),
+                    Range::RangeToI { end } => (None, Some(end), true),
+                };
+                let start = start
+                    .map(Node::<Instruction>::try_convert_from)
+                    .transpose()?;
+                let end = end.map(Node::<Instruction>::try_convert_from).transpose()?;
+                let bound = |x: &Option<Node<Instruction>>| match x.as_ref().map(|x| &*x.data) {
+                    Some(Instruction::Value(Value::Primitive(Primitive::Integer(x)))) => {
+                        Ok(Some(Some(*x)))
+                    }
+                    Some(Instruction::Value(_)) => Err(Error::TypeMismatch),
+                    Some(_) => Ok(None),
+                    None => Ok(Some(None)),
+                };
+                match (bound(&start)?, bound(&end)?) {
+                    (Some(start), Some(end)) => Ok(Instruction::Value(Value::Range(hir::Range {
+                        start,
+                        end,
+                        inclusive,
+                    }))),
+                    _ => Ok(Instruction::Range(RangeExpr {
+                        start,
+                        end,
+                        inclusive,
+                    })),
+                }
+            }
+            Op::Arith(lhs, op, rhs) => {
+                let lhs = Node::<Instruction>::try_convert_from(lhs)?;
+                let rhs = Node::<Instruction>::try_convert_from(rhs)?;
+                match (*lhs.data, *rhs.data) {
+                    (
+                        Instruction::Value(Value::Primitive(lhs)),
+                        Instruction::Value(Value::Primitive(rhs)),
+                    ) => match lhs.arith(*op, rhs) {
+                        Ok(val) => Ok(Instruction::Value(Value::Primitive(val))),
+                        Err(hir::Error::InvalidOp) => Err(Error::InvalidOp),
+                        Err(_) => Err(Error::TypeMismatch),
                     },
-                    _ => todo!(),
+                    (Instruction::Value(_), Instruction::Value(_)) => Err(Error::TypeMismatch),
+                    (lhs_data, rhs_data) => Ok(Instruction::Arith(
+                        Node::new(lhs.span, lhs_data),
+                        *op,
+                        Node::new(rhs.span, rhs_data),
+                    )),
                 }
             }
             Op::Unary(op, rhs) => {
                 let rhs = Node::<Instruction>::try_convert_from(rhs)?;
                 match *rhs.data {
-                    Instruction::Value(rhs) => Ok(Instruction::Value(match rhs {
-                        Value::Primitive(Primitive::Integer(val)) => match *op {
-                            OpArith::ADD => rhs,
-                            OpArith::SUB => Value::Primitive(match val.checked_neg() {
-                                Some(val) => Primitive::Integer(val),
-                                None => Primitive::Float((val as Float) * -1.),
-                            }),
-                            _ => return Err(Error::InvalidOp),
-                        },
-                        Value::Primitive(Primitive::Float(val)) => match *op {
-                            OpArith::ADD => rhs,
-                            OpArith::SUB => Value::Primitive(Primitive::Float(val * -1.)),
-                            _ => return Err(Error::InvalidOp),
-                        },
-                        _ => return Err(Error::TypeMismatch),
-                    })),
-                    Instruction::Variable(_) => todo!(),
-                    Instruction::Action(_) => todo!(),
-                    Instruction::Block(_) => todo!(),
-                    Instruction::Assign(_, _) => todo!(),
-                    Instruction::Symbol(_) => todo!(),
+                    Instruction::Value(Value::Primitive(rhs)) => match rhs.unary(*op) {
+                        Ok(val) => Ok(Instruction::Value(Value::Primitive(val))),
+                        Err(hir::Error::InvalidOp) => Err(Error::InvalidOp),
+                        Err(_) => Err(Error::TypeMismatch),
+                    },
+                    Instruction::Value(_) => Err(Error::TypeMismatch),
+                    rhs_data => Ok(Instruction::Unary(*op, Node::new(rhs.span, rhs_data))),
                 }
             }
-            Op::Coefficient(_, _) => todo!(),
-            Op::Die(_) => todo!(),
+            Op::Coefficient(lhs, rhs) => match *rhs.data {
+                Expr::Op(x @ (Op::Die(_, _) | Op::Fudge(_, _))) => dice(Some(lhs), x),
+                rhs_data => {
+                    let op = Node::new(lhs.span.end..rhs.span.start, OpArith::MUL);
+                    let lhs = Node::convert(|x| Expr::Primary(Primary::Atom(x)), lhs);
+                    let rhs = Node::new(rhs.span, rhs_data);
+                    Instruction::try_from(Op::Arith(lhs, op, rhs))
+                }
+            },
+            x @ (Op::Die(_, _) | Op::Fudge(_, _)) => dice(None, x),
         }
     }
 }
+
+impl TryFrom<DieMod> for hir::DieMod {
+    type Error = Error;
+
+    fn try_from(val: DieMod) -> Result<Self, Self::Error> {
+        fn integer(val: Option<Node<Atom>>, span: Span) -> Result<Node<Instruction>, Error> {
+            match val {
+                Some(val) => Ok(Node::<Instruction>::try_convert_from(val)?),
+                None => Ok(Node::new(
+                    span,
+                    Instruction::Value(Value::Primitive(Primitive::Integer(1))),
+                )),
+            }
+        }
+
+        match val {
+            DieMod::Keep(op, val) => Ok(hir::DieMod::Keep(*op, integer(val, op.span)?)),
+            DieMod::Explode(op, val) => Ok(hir::DieMod::Explode(
+                *op,
+                val.map(hir::DieCompare::try_from).transpose()?,
+            )),
+            DieMod::Reroll(op, val) => Ok(hir::DieMod::Reroll(
+                *op,
+                val.map(hir::DieCompare::try_from).transpose()?,
+            )),
+            DieMod::Success(val) => Ok(hir::DieMod::Success(hir::DieCompare::try_from(val)?)),
+            DieMod::Failure(val) => Ok(hir::DieMod::Failure(hir::DieCompare::try_from(val)?)),
+            DieMod::Crit(op, val) => Ok(hir::DieMod::Crit(
+                *op,
+                val.map(hir::DieCompare::try_from).transpose()?,
+            )),
+        }
+    }
+}
+
+impl TryFrom<Node<DieCompare>> for hir::DieCompare {
+    type Error = Error;
+
+    fn try_from(val: Node<DieCompare>) -> Result<Self, Self::Error> {
+        let DieCompare { op, val } = *val.data;
+        Ok(hir::DieCompare(
+            op.map(|x| *x.data).unwrap_or(OpEqs::EQ),
+            Node::<Instruction>::try_convert_from(val)?,
+        ))
+    }
+}
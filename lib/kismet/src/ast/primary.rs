@@ -1,7 +1,10 @@
 use std::fmt;
 
 use super::{Args, Atom, Error, Expr, Id};
-use crate::{hir::Instruction, types::Node};
+use crate::{
+    hir::{self, Instruction, Primitive, Value},
+    types::{Integer, Node},
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Primary {
@@ -31,10 +34,42 @@ impl TryFrom<Primary> for Instruction {
 
     fn try_from(val: Primary) -> Result<Self, Self::Error> {
         match val {
-            Primary::Attribute(_, _) => todo!(),
-            Primary::Index(_, _) => todo!(),
-            Primary::Subscription(_, _) => todo!(),
-            Primary::Call(_, _) => todo!(),
+            Primary::Attribute(lhs, id) => Ok(Instruction::Index(
+                Node::<Instruction>::try_convert_from(lhs)?,
+                Node::convert(
+                    |x: Id| Instruction::Value(Value::Primitive(Primitive::String(x.0))),
+                    id,
+                ),
+            )),
+            Primary::Index(lhs, idx) => Ok(Instruction::Index(
+                Node::<Instruction>::try_convert_from(lhs)?,
+                Node::new(
+                    idx.span,
+                    Instruction::Value(Value::Primitive(Primitive::Integer(
+                        Integer::try_from(*idx.data).map_err(|_| Error::InvalidOp)?,
+                    ))),
+                ),
+            )),
+            Primary::Subscription(lhs, idx) => {
+                let lhs = Node::<Instruction>::try_convert_from(lhs)?;
+                let val = idx.into_iter().try_fold(lhs, |lhs, idx| {
+                    let idx = Node::<Instruction>::try_convert_from(idx)?;
+                    Ok::<_, Error>(Node::new(lhs.span + idx.span, Instruction::Index(lhs, idx)))
+                })?;
+                Ok(*val.data)
+            }
+            Primary::Call(lhs, args) => {
+                let args = args
+                    .data
+                    .0
+                    .into_iter()
+                    .map(Node::<Instruction>::try_convert_from)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Instruction::Call(
+                    Node::<Instruction>::try_convert_from(lhs)?,
+                    hir::Args(args),
+                ))
+            }
             Primary::Atom(x) => Instruction::try_from(x),
         }
     }
@@ -1,8 +1,11 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
-use crate::types::Node;
+use crate::{
+    hir::{Bind, Instruction, Pattern, Var},
+    types::Node,
+};
 
-use super::{Expr, Id, Target};
+use super::{Error, Expr, Id, Target, TargetKind};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Stmt {
@@ -29,3 +32,35 @@ impl fmt::Display for Stmt {
         }
     }
 }
+
+impl TryFrom<Stmt> for Instruction {
+    type Error = Error;
+
+    fn try_from(val: Stmt) -> Result<Self, Self::Error> {
+        match val {
+            Stmt::Return(val) => Ok(Instruction::Return(Node::<Instruction>::try_convert_from(
+                val,
+            )?)),
+            Stmt::Break { id, val } => Ok(Instruction::Break(
+                id.map(|x| *x.data),
+                Node::<Instruction>::try_convert_from(val)?,
+            )),
+            Stmt::Assign { tar, val } => assign(Bind::Rebind, tar, val),
+        }
+    }
+}
+
+pub(super) fn assign(kind: Bind, tar: Node<Target>, val: Node<Expr>) -> Result<Instruction, Error> {
+    let mut val = Node::<Instruction>::try_convert_from(val)?;
+    if let (TargetKind::Id(id), Instruction::Function(x)) = (&tar.data.0, &mut *val.data) {
+        Arc::make_mut(x).name = Some(Var::Id(id.clone()));
+    }
+    match &tar.data.0 {
+        TargetKind::Id(id) => Ok(Instruction::Assign(kind, id.clone(), val)),
+        _ => Ok(Instruction::Destructure(
+            kind,
+            Node::<Pattern>::try_convert_from::<Target, _>(tar)?,
+            val,
+        )),
+    }
+}
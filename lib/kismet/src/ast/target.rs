@@ -1,8 +1,11 @@
 use std::fmt;
 
-use crate::types::Node;
+use crate::{
+    hir::{Pattern, PatternDictItem, PatternItem, Primitive, Value},
+    types::Node,
+};
 
-use super::{Atom, DictItem, Expr, Id, ListItem, Primary};
+use super::{Atom, DictItem, Error, Expr, Id, ListItem, Primary};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Target(pub TargetKind<Target>);
@@ -361,3 +364,98 @@ where
         }
     }
 }
+
+impl<T> TryFrom<TargetKind<T>> for Pattern
+where
+    Pattern: TryFrom<T, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(val: TargetKind<T>) -> Result<Self, Self::Error> {
+        fn list<T>(val: Vec<Node<TargetListItem<T>>>) -> Result<Vec<PatternItem>, Error>
+        where
+            Pattern: TryFrom<T, Error = Error>,
+        {
+            let mut spread = false;
+            val.into_iter()
+                .map(|x| match *x.data {
+                    TargetListItem::Target(x) => Ok(PatternItem::Pattern(Pattern::try_from(x)?)),
+                    TargetListItem::Spread(_) if spread => Err(Error::InvalidOp),
+                    TargetListItem::Spread(x) => {
+                        spread = true;
+                        Ok(PatternItem::Spread(Pattern::try_from(*x.data)?))
+                    }
+                })
+                .collect()
+        }
+
+        match val {
+            TargetKind::Id(x) => Ok(Pattern::Id(x)),
+            TargetKind::TargetTuple(x) => Ok(Pattern::Tuple(list::<T>(x)?)),
+            TargetKind::TargetList(x) => Ok(Pattern::List(list::<T>(x)?)),
+            TargetKind::TargetDict(x) => {
+                let mut spread = false;
+                let x = x
+                    .into_iter()
+                    .map(|x| match *x.data {
+                        TargetDictItem::KeyVal { key, val } => Ok(PatternDictItem::KeyVal(
+                            *key.data,
+                            Pattern::try_from(*val.data)?,
+                        )),
+                        TargetDictItem::Spread(_) if spread => Err(Error::InvalidOp),
+                        TargetDictItem::Spread(x) => {
+                            spread = true;
+                            Ok(PatternDictItem::Spread(Pattern::try_from(*x.data)?))
+                        }
+                        TargetDictItem::Target(x) => match Pattern::try_from(x)? {
+                            Pattern::Id(x) => {
+                                Ok(PatternDictItem::KeyVal(x.clone(), Pattern::Id(x)))
+                            }
+                            _ => Err(Error::InvalidOp),
+                        },
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Pattern::Dict(x))
+            }
+        }
+    }
+}
+
+impl TryFrom<TargetExpr> for Pattern {
+    type Error = Error;
+
+    fn try_from(val: TargetExpr) -> Result<Self, Self::Error> {
+        match val {
+            TargetExpr::Target(x) => Pattern::try_from(x),
+            TargetExpr::TargetExpr(_, _) => Err(Error::InvalidOp),
+        }
+    }
+}
+
+impl TryFrom<Target> for Pattern {
+    type Error = Error;
+
+    fn try_from(val: Target) -> Result<Self, Self::Error> {
+        Pattern::try_from(val.0)
+    }
+}
+
+impl TryFrom<Match> for Pattern {
+    type Error = Error;
+
+    fn try_from(val: Match) -> Result<Self, Self::Error> {
+        match val {
+            Match::Target(x) => Pattern::try_from(x),
+            Match::Literal(Atom::Integer(x)) => {
+                Ok(Pattern::Literal(Value::Primitive(Primitive::Integer(x))))
+            }
+            Match::Literal(Atom::Float(x)) => {
+                Ok(Pattern::Literal(Value::Primitive(Primitive::Float(x))))
+            }
+            Match::Literal(Atom::String(x)) => {
+                Ok(Pattern::Literal(Value::Primitive(Primitive::String(x))))
+            }
+            Match::Literal(_) => Err(Error::InvalidOp),
+        }
+    }
+}
@@ -2,19 +2,20 @@ use std::slice::Iter;
 
 use crate::{ast, types::Node};
 
-use super::{Error, Exec, Instruction, SymbolTable, SymbolTableResult, Value};
+use super::{Context, Error, Exec, Instruction, Value};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Block(pub Vec<Node<Instruction>>);
 
-impl Exec<SymbolTable, (SymbolTable, Value), Error> for Block {
-    fn exec(&self, i: SymbolTable) -> SymbolTableResult {
-        self.0
+impl Exec<Context, Value, Error> for Block {
+    fn exec(&self, i: &mut Context) -> Result<Value, Error> {
+        i.begin();
+        let res = self
+            .0
             .iter()
-            .fold(Ok((i, Value::default())), move |acc, val| {
-                let (i, _) = acc?;
-                Ok(val.exec(i)?)
-            })
+            .try_fold(Value::default(), |_, val| Ok(val.exec(i)?));
+        i.end();
+        res
     }
 }
 
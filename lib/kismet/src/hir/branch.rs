@@ -0,0 +1,93 @@
+use crate::types::Node;
+
+use super::{Analysis, Block, Context, Dist, Error, Exec, Instruction, Pattern, Primitive, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Branch {
+    If {
+        val: Node<Instruction>,
+        t_block: Node<Block>,
+        f_block: Node<Block>,
+    },
+    Match {
+        val: Node<Instruction>,
+        arms: Vec<MatchArm>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchArm {
+    pub tar: Node<Pattern>,
+    pub block: Node<Block>,
+}
+
+impl Exec<Context, Value, Error> for Branch {
+    fn exec(&self, i: &mut Context) -> Result<Value, Error> {
+        match self {
+            Self::If {
+                val,
+                t_block,
+                f_block,
+            } => {
+                let block = match val.exec(i)?.truthy() {
+                    true => t_block,
+                    false => f_block,
+                };
+                i.sym.push();
+                let res = block.exec(i);
+                i.sym.pop();
+                Ok(res?)
+            }
+            Self::Match { val, arms } => {
+                let val = val.exec(i)?;
+                for arm in arms {
+                    if let Some(binds) = arm.tar.matches(val.clone()) {
+                        i.sym.push();
+                        for (key, val) in binds {
+                            i.declare(key, val);
+                        }
+                        let res = arm.block.exec(i);
+                        i.sym.pop();
+                        return Ok(res?);
+                    }
+                }
+                Ok(Value::default())
+            }
+        }
+    }
+}
+
+impl Exec<Analysis, Dist, Error> for Branch {
+    fn exec(&self, i: &mut Analysis) -> Result<Dist, Error> {
+        let val: Dist = match self {
+            Self::If { val, .. } | Self::Match { val, .. } => val.exec(i)?,
+        };
+        let (sym, stack) = (i.sym.clone(), i.stack.clone());
+        let res = val.bind(|x| {
+            i.sym = sym.clone();
+            i.stack = stack.clone();
+            i.sym.push();
+            match self {
+                Self::If {
+                    t_block, f_block, ..
+                } => match x.truthy() {
+                    true => Ok(t_block.exec(i)?),
+                    false => Ok(f_block.exec(i)?),
+                },
+                Self::Match { arms, .. } => {
+                    for arm in arms {
+                        if let Some(binds) = arm.tar.matches(Value::Primitive(x.clone())) {
+                            for (key, val) in binds {
+                                i.declare(key, val);
+                            }
+                            return Ok(arm.block.exec(i)?);
+                        }
+                    }
+                    Ok(Dist::constant(Primitive::default()))
+                }
+            }
+        });
+        (i.sym, i.stack) = (sym, stack);
+        res
+    }
+}
@@ -15,7 +15,7 @@ pub enum Collection {
 
 impl fmt::Display for Collection {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &*self {
+        match self {
             Self::Tuple(x) => write!(
                 f,
                 "({}{})",
@@ -0,0 +1,249 @@
+use std::{collections::VecDeque, fmt, mem, sync::Arc};
+
+use indexmap::IndexMap;
+
+use crate::{ast::Id, types::Node};
+
+use super::{
+    Collection, Context, DictItemComp, Error, Exec, Instruction, ListItem, Pattern, Primitive,
+    SymbolTable, Value,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CompIter {
+    For {
+        tar: Node<Pattern>,
+        val: Node<Instruction>,
+    },
+    If(Node<Instruction>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comprehension<T> {
+    pub val: Node<T>,
+    pub iter: Vec<Node<CompIter>>,
+    pub captures: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Generator {
+    pub comp: Arc<Comprehension<ListItem<Instruction>>>,
+    pub env: SymbolTable,
+    pub slots: Vec<Value>,
+}
+
+pub enum Cursor {
+    Iter(Box<dyn Iterator<Item = Value>>),
+    Generator(Box<GeneratorCursor>),
+}
+
+pub struct GeneratorCursor {
+    walk: Walk<ListItem<Instruction>>,
+    pending: VecDeque<Value>,
+}
+
+struct Walk<T> {
+    comp: Arc<Comprehension<T>>,
+    env: SymbolTable,
+    slots: Vec<Value>,
+    frames: Vec<(usize, Cursor)>,
+    started: bool,
+}
+
+impl<T> Walk<T> {
+    fn new(comp: Arc<Comprehension<T>>, mut env: SymbolTable, slots: Vec<Value>) -> Self {
+        env.push();
+        Walk {
+            comp,
+            env,
+            slots,
+            frames: vec![],
+            started: false,
+        }
+    }
+
+    fn enter(&mut self, i: &mut Context) {
+        mem::swap(&mut self.env, &mut i.sym);
+        i.stack.push_frame(mem::take(&mut self.slots), &[]);
+    }
+
+    fn exit(&mut self, i: &mut Context) {
+        self.slots = i.stack.pop_frame();
+        mem::swap(&mut self.env, &mut i.sym);
+    }
+
+    fn advance(&mut self, i: &mut Context) -> Result<bool, Error> {
+        self.enter(i);
+        let res = self.step(i);
+        self.exit(i);
+        res
+    }
+
+    fn eval<R>(
+        &mut self,
+        i: &mut Context,
+        f: impl FnOnce(&T, &mut Context) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        self.enter(i);
+        let res = f(&self.comp.val.data, i);
+        self.exit(i);
+        res
+    }
+
+    fn step(&mut self, i: &mut Context) -> Result<bool, Error> {
+        let mut pos = match self.started {
+            true => None,
+            false => {
+                self.started = true;
+                Some(0)
+            }
+        };
+        loop {
+            let idx = match pos {
+                Some(idx) => idx,
+                None => {
+                    let Some((idx, cursor)) = self.frames.last_mut() else {
+                        return Ok(false);
+                    };
+                    i.tick()?;
+                    match cursor.next(i)? {
+                        Some(x) => {
+                            if let CompIter::For { tar, .. } = &*self.comp.iter[*idx].data {
+                                for (key, val) in tar.destructure(x)? {
+                                    i.declare(key, val);
+                                }
+                            }
+                            *idx + 1
+                        }
+                        None => {
+                            self.frames.pop();
+                            continue;
+                        }
+                    }
+                }
+            };
+            pos = match self.comp.iter.get(idx).map(|x| &*x.data) {
+                None => return Ok(true),
+                Some(CompIter::If(val)) => match val.exec(i)?.truthy() {
+                    true => Some(idx + 1),
+                    false => None,
+                },
+                Some(CompIter::For { val, .. }) => {
+                    self.frames.push((idx, val.exec(i)?.cursor()?));
+                    None
+                }
+            };
+        }
+    }
+}
+
+impl Cursor {
+    pub fn next(&mut self, i: &mut Context) -> Result<Option<Value>, Error> {
+        match self {
+            Self::Iter(x) => Ok(x.next()),
+            Self::Generator(x) => loop {
+                if let Some(val) = x.pending.pop_front() {
+                    return Ok(Some(val));
+                }
+                if !x.walk.advance(i)? {
+                    return Ok(None);
+                }
+                let val = x.walk.eval(i, items)?;
+                x.pending.extend(val);
+            },
+        }
+    }
+}
+
+impl Value {
+    pub fn cursor(self) -> Result<Cursor, Error> {
+        match self {
+            Self::Generator(x) => Ok(Cursor::Generator(Box::new(GeneratorCursor {
+                walk: Walk::new(x.comp, x.env, x.slots),
+                pending: VecDeque::new(),
+            }))),
+            x => Ok(Cursor::Iter(x.iterate()?)),
+        }
+    }
+
+    pub fn spread(self, i: &mut Context) -> Result<Vec<Value>, Error> {
+        match self {
+            Self::Collection(Collection::List(x) | Collection::Tuple(x)) => Ok(x),
+            Self::Range(x) => match x.len() {
+                Some(len) if len > i.max_iter => Err(Error::IterationLimit),
+                Some(_) => Ok(x
+                    .iter()
+                    .map(|x| Value::Primitive(Primitive::Integer(x)))
+                    .collect()),
+                None => Err(Error::InvalidOp),
+            },
+            Self::Generator(_) => {
+                let mut cursor = self.cursor()?;
+                let mut vec = vec![];
+                while let Some(x) = cursor.next(i)? {
+                    vec.push(x);
+                }
+                Ok(vec)
+            }
+            _ => Err(Error::TypeMismatch),
+        }
+    }
+}
+
+fn items(val: &ListItem<Instruction>, i: &mut Context) -> Result<Vec<Value>, Error> {
+    match val {
+        ListItem::Expr(x) => Ok(vec![x.exec(i)?]),
+        ListItem::Spread(x) => x.exec(i)?.spread(i),
+    }
+}
+
+impl Comprehension<ListItem<Instruction>> {
+    pub fn generator(self: &Arc<Self>, i: &mut Context) -> Value {
+        Value::Generator(Generator {
+            comp: self.clone(),
+            env: i.sym.clone(),
+            slots: i.stack.capture(&self.captures),
+        })
+    }
+
+    pub fn list(self: &Arc<Self>, i: &mut Context) -> Result<Value, Error> {
+        let mut walk = Walk::new(self.clone(), i.sym.clone(), i.stack.capture(&self.captures));
+        let mut vec = vec![];
+        while walk.advance(i)? {
+            vec.append(&mut walk.eval(i, items)?);
+        }
+        Ok(Value::Collection(Collection::List(vec)))
+    }
+}
+
+impl Comprehension<DictItemComp<Instruction>> {
+    pub fn dict(self: &Arc<Self>, i: &mut Context) -> Result<Value, Error> {
+        let mut walk = Walk::new(self.clone(), i.sym.clone(), i.stack.capture(&self.captures));
+        let mut map = IndexMap::new();
+        while walk.advance(i)? {
+            walk.eval(i, |val, i| match val {
+                DictItemComp::DynKeyVal { key, val } => match key.exec(i)? {
+                    Value::Primitive(Primitive::String(key)) => {
+                        map.insert(Id(key), val.exec(i)?);
+                        Ok(())
+                    }
+                    _ => Err(Error::TypeMismatch),
+                },
+                DictItemComp::Spread(x) => match x.exec(i)? {
+                    Value::Collection(Collection::Dict(x)) => {
+                        map.extend(x);
+                        Ok(())
+                    }
+                    _ => Err(Error::TypeMismatch),
+                },
+            })?;
+        }
+        Ok(Value::Collection(Collection::Dict(map)))
+    }
+}
+
+impl fmt::Display for Generator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "generator")
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{Bind, Error, Stack, SymbolTable, Value, Var};
+
+pub const MAX_DICE: usize = 1_000;
+pub const MAX_EXPLODE: usize = 100;
+pub const MAX_REROLL: usize = 100;
+pub const MAX_ITER: usize = 10_000;
+pub const MAX_DEPTH: usize = 64;
+
+pub struct Context {
+    pub sym: SymbolTable,
+    pub stack: Stack,
+    pub max_dice: usize,
+    pub max_explode: usize,
+    pub max_reroll: usize,
+    pub max_iter: usize,
+    pub max_depth: usize,
+    depth: usize,
+    iter: usize,
+    nest: usize,
+    rng: Box<dyn RngCore + Send>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::with_rng(StdRng::from_entropy())
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        Self::with_rng(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    pub fn with_rng<R>(rng: R) -> Self
+    where
+        R: RngCore + Send + 'static,
+    {
+        Context {
+            sym: SymbolTable::default(),
+            stack: Stack::default(),
+            max_dice: MAX_DICE,
+            max_explode: MAX_EXPLODE,
+            max_reroll: MAX_REROLL,
+            max_iter: MAX_ITER,
+            max_depth: MAX_DEPTH,
+            depth: 0,
+            iter: 0,
+            nest: 0,
+            rng: Box::new(rng),
+        }
+    }
+
+    pub fn rng(&mut self) -> &mut (dyn RngCore + Send) {
+        &mut *self.rng
+    }
+
+    pub fn bind(&mut self, kind: Bind, key: Var, val: Value) -> Result<(), Error> {
+        match key {
+            Var::Id(x) => {
+                self.sym.bind(kind, x, val)?;
+            }
+            Var::Local(x) => self.stack.set(x, val)?,
+        }
+        Ok(())
+    }
+
+    pub fn declare(&mut self, key: Var, val: Value) {
+        match key {
+            Var::Id(x) => {
+                self.sym.declare(x, val);
+            }
+            Var::Local(x) => self.stack.store(x, val),
+        }
+    }
+
+    pub fn enter(&mut self) -> Result<(), Error> {
+        if self.depth >= self.max_depth {
+            return Err(Error::RecursionLimit);
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub fn exit(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    pub fn begin(&mut self) {
+        if self.nest == 0 {
+            self.iter = 0;
+        }
+        self.nest += 1;
+    }
+
+    pub fn end(&mut self) {
+        self.nest = self.nest.saturating_sub(1);
+    }
+
+    pub fn tick(&mut self) -> Result<(), Error> {
+        if self.iter >= self.max_iter {
+            return Err(Error::IterationLimit);
+        }
+        self.iter += 1;
+        Ok(())
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("sym", &self.sym)
+            .field("stack", &self.stack)
+            .field("max_dice", &self.max_dice)
+            .field("max_explode", &self.max_explode)
+            .field("max_reroll", &self.max_reroll)
+            .field("max_iter", &self.max_iter)
+            .field("max_depth", &self.max_depth)
+            .finish_non_exhaustive()
+    }
+}
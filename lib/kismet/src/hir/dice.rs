@@ -0,0 +1,386 @@
+use std::fmt;
+
+use rand::Rng;
+
+use crate::{
+    ast::{OpCrit, OpEqs, OpExplode, OpKeep, OpReroll},
+    types::{Float, Integer, Node},
+};
+
+use super::{Collection, Context, Error, Exec, Instruction, Primitive, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dice {
+    pub notation: String,
+    pub count: Node<Instruction>,
+    pub sides: Node<Instruction>,
+    pub mods: Vec<Node<DieMod>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DieMod {
+    Keep(OpKeep, Node<Instruction>),
+    Explode(OpExplode, Option<DieCompare>),
+    Reroll(OpReroll, Option<DieCompare>),
+    Success(DieCompare),
+    Failure(DieCompare),
+    Crit(OpCrit, Option<DieCompare>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DieCompare(pub OpEqs, pub Node<Instruction>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sides {
+    Range(Integer),
+    Faces(Vec<Integer>),
+    Labels(Vec<Value>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Roll {
+    pub notation: String,
+    pub dice: Vec<Die>,
+    pub sides: Sides,
+    pub count: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Die {
+    pub face: Integer,
+    pub kept: bool,
+    pub exploded: bool,
+    pub rerolled: Vec<Integer>,
+    pub success: bool,
+    pub failure: bool,
+    pub crit: Option<OpCrit>,
+}
+
+impl Dice {
+    pub fn roll<R>(rng: &mut R, count: Integer, sides: Integer) -> Result<Roll, Error>
+    where
+        R: Rng + ?Sized,
+    {
+        Sides::Range(sides).roll(rng, count)
+    }
+
+    pub fn eval(&self, i: &mut Context) -> Result<Roll, Error> {
+        let count = integer(self.count.exec(i)?)?;
+        let sides = Sides::try_from(self.sides.exec(i)?)?;
+        if matches!(sides, Sides::Labels(_)) && !self.mods.is_empty() {
+            return Err(Error::InvalidOp);
+        }
+        if count > i.max_dice as Integer {
+            return Err(Error::DiceLimit);
+        }
+        let (min, max) = (sides.min(), sides.max());
+        let mut roll = sides.roll(i.rng(), count)?;
+        roll.notation = self.notation.clone();
+        for x in self.mods.iter() {
+            if let DieMod::Reroll(op, val) = &*x.data {
+                let (cmp, val) = DieCompare::eval_or(val, min, i)?;
+                let depth = match op {
+                    OpReroll::REROLL => i.max_reroll,
+                    OpReroll::ONCE => 1,
+                };
+                roll.reroll(i.rng(), cmp, val, depth)
+            }
+        }
+        for x in self.mods.iter() {
+            if let DieMod::Explode(op, val) = &*x.data {
+                let (cmp, val) = DieCompare::eval_or(val, max, i)?;
+                let depth = i.max_explode;
+                roll.explode(i.rng(), *op, cmp, val, depth)
+            }
+        }
+        for x in self.mods.iter() {
+            if let DieMod::Keep(op, val) = &*x.data {
+                let val = integer(val.exec(i)?)?;
+                roll.keep(*op, val)?
+            }
+        }
+        for x in self.mods.iter() {
+            match &*x.data {
+                DieMod::Success(val) => {
+                    let (cmp, val) = val.eval(i)?;
+                    roll.count = true;
+                    for x in roll.dice.iter_mut() {
+                        x.success |= cmp.compare(&x.face, &val);
+                    }
+                }
+                DieMod::Failure(val) => {
+                    let (cmp, val) = val.eval(i)?;
+                    roll.count = true;
+                    for x in roll.dice.iter_mut() {
+                        x.failure |= cmp.compare(&x.face, &val);
+                    }
+                }
+                DieMod::Crit(op, val) => {
+                    let (cmp, val) = match op {
+                        OpCrit::SUCCESS => DieCompare::eval_or(val, max, i)?,
+                        OpCrit::FAILURE => DieCompare::eval_or(val, min, i)?,
+                    };
+                    for x in roll.dice.iter_mut() {
+                        if cmp.compare(&x.face, &val) {
+                            x.crit = Some(*op);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(roll)
+    }
+}
+
+fn integer(val: Value) -> Result<Integer, Error> {
+    match Primitive::try_from(val) {
+        Ok(Primitive::Integer(val)) => Ok(val),
+        _ => Err(Error::TypeMismatch),
+    }
+}
+
+impl DieCompare {
+    pub fn eval(&self, i: &mut Context) -> Result<(OpEqs, Integer), Error> {
+        Ok((self.0, integer(self.1.exec(i)?)?))
+    }
+
+    fn eval_or(
+        val: &Option<DieCompare>,
+        default: Integer,
+        i: &mut Context,
+    ) -> Result<(OpEqs, Integer), Error> {
+        match val {
+            Some(val) => val.eval(i),
+            None => Ok((OpEqs::EQ, default)),
+        }
+    }
+}
+
+impl Exec<Context, Value, Error> for Dice {
+    fn exec(&self, i: &mut Context) -> Result<Value, Error> {
+        Ok(self.eval(i)?.value())
+    }
+}
+
+impl Sides {
+    pub fn roll<R>(&self, rng: &mut R, count: Integer) -> Result<Roll, Error>
+    where
+        R: Rng + ?Sized,
+    {
+        let valid = match self {
+            Sides::Range(x) => *x >= 1,
+            Sides::Faces(x) => !x.is_empty(),
+            Sides::Labels(x) => !x.is_empty(),
+        };
+        if count < 0 || !valid {
+            return Err(Error::InvalidOp);
+        }
+        Ok(Roll {
+            notation: String::new(),
+            dice: (0..count).map(|_| Die::new(self.sample(rng))).collect(),
+            sides: self.clone(),
+            count: false,
+        })
+    }
+
+    pub fn sample<R>(&self, rng: &mut R) -> Integer
+    where
+        R: Rng + ?Sized,
+    {
+        match self {
+            Sides::Range(x) => rng.gen_range(1..=*x),
+            Sides::Faces(x) => x[rng.gen_range(0..x.len())],
+            Sides::Labels(x) => rng.gen_range(1..=x.len() as Integer),
+        }
+    }
+
+    pub fn min(&self) -> Integer {
+        match self {
+            Sides::Faces(x) => x.iter().copied().min().unwrap_or(1),
+            _ => 1,
+        }
+    }
+
+    pub fn max(&self) -> Integer {
+        match self {
+            Sides::Range(x) => *x,
+            Sides::Faces(x) => x.iter().copied().max().unwrap_or(1),
+            Sides::Labels(x) => x.len() as Integer,
+        }
+    }
+}
+
+impl TryFrom<Value> for Sides {
+    type Error = Error;
+
+    fn try_from(val: Value) -> Result<Self, Self::Error> {
+        match val {
+            Value::Collection(Collection::List(x)) => x
+                .iter()
+                .map(|x| match x {
+                    Value::Primitive(Primitive::Integer(x)) => Some(*x),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map_or(Ok(Sides::Labels(x)), |x| Ok(Sides::Faces(x))),
+            val => Ok(Sides::Range(integer(val)?)),
+        }
+    }
+}
+
+impl Die {
+    pub fn new(face: Integer) -> Self {
+        Die {
+            face,
+            kept: true,
+            exploded: false,
+            rerolled: vec![],
+            success: false,
+            failure: false,
+            crit: None,
+        }
+    }
+}
+
+impl Roll {
+    pub fn kept(&self) -> impl Iterator<Item = &Die> {
+        self.dice.iter().filter(|x| x.kept)
+    }
+
+    pub fn dropped(&self) -> impl Iterator<Item = &Die> {
+        self.dice.iter().filter(|x| !x.kept)
+    }
+
+    pub fn value(self) -> Value {
+        match &self.sides {
+            Sides::Labels(x) => Value::Collection(Collection::List(
+                self.kept()
+                    .map(|d| x[d.face as usize - 1].clone())
+                    .collect(),
+            )),
+            _ => Value::Roll(self),
+        }
+    }
+
+    pub fn total(&self) -> Primitive {
+        if self.count {
+            return Primitive::Integer(
+                self.kept()
+                    .map(|x| x.success as Integer - x.failure as Integer)
+                    .sum(),
+            );
+        }
+        match self
+            .kept()
+            .try_fold(0 as Integer, |acc, x| acc.checked_add(x.face))
+        {
+            Some(x) => Primitive::Integer(x),
+            None => Primitive::Float(self.kept().map(|x| x.face as Float).sum()),
+        }
+    }
+
+    pub fn reroll<R>(&mut self, rng: &mut R, cmp: OpEqs, val: Integer, depth: usize)
+    where
+        R: Rng + ?Sized,
+    {
+        for x in self.dice.iter_mut() {
+            while cmp.compare(&x.face, &val) && x.rerolled.len() < depth {
+                x.rerolled.push(x.face);
+                x.face = self.sides.sample(rng);
+            }
+        }
+    }
+
+    pub fn explode<R>(&mut self, rng: &mut R, op: OpExplode, cmp: OpEqs, val: Integer, depth: usize)
+    where
+        R: Rng + ?Sized,
+    {
+        let mut dice = Vec::with_capacity(self.dice.len());
+        for mut x in self.dice.drain(..) {
+            let mut last = x.face;
+            let mut n = 0;
+            while cmp.compare(&last, &val) && n < depth {
+                last = self.sides.sample(rng);
+                n += 1;
+                let face = match op {
+                    OpExplode::PENETRATE => last - 1,
+                    _ => last,
+                };
+                match op {
+                    OpExplode::COMPOUND => {
+                        x.face = x.face.saturating_add(face);
+                        x.exploded = true;
+                    }
+                    _ => {
+                        x.exploded = true;
+                        dice.push(x);
+                        x = Die::new(face);
+                    }
+                }
+            }
+            dice.push(x);
+        }
+        self.dice = dice;
+    }
+
+    pub fn keep(&mut self, op: OpKeep, n: Integer) -> Result<(), Error> {
+        let n = usize::try_from(n).map_err(|_| Error::InvalidOp)?;
+        let mut idx = self
+            .dice
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.kept)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        idx.sort_by_key(|x| self.dice[*x].face);
+        let len = idx.len();
+        let drop = match op {
+            OpKeep::KH => &idx[..len.saturating_sub(n)],
+            OpKeep::KL => &idx[len.min(n)..],
+            OpKeep::DH => &idx[len.saturating_sub(n)..],
+            OpKeep::DL => &idx[..len.min(n)],
+        };
+        for x in drop {
+            self.dice[*x].kept = false;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Roll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let faces = self
+            .dice
+            .iter()
+            .flat_map(|x| {
+                let face = match (&self.sides, x.kept) {
+                    (Sides::Labels(val), true) => val[x.face as usize - 1].to_string(),
+                    (Sides::Labels(val), false) => format!("~~{}~~", val[x.face as usize - 1]),
+                    (_, true) => x.face.to_string(),
+                    (_, false) => format!("~~{}~~", x.face),
+                };
+                let marks = [
+                    (x.exploded, "!"),
+                    (x.success, "+"),
+                    (x.failure, "-"),
+                    (x.crit == Some(OpCrit::SUCCESS), "^"),
+                    (x.crit == Some(OpCrit::FAILURE), "_"),
+                ];
+                let face = marks
+                    .iter()
+                    .filter(|(set, _)| *set)
+                    .fold(face, |face, (_, mark)| face + mark);
+                x.rerolled
+                    .iter()
+                    .map(|x| format!("({})", x))
+                    .chain(std::iter::once(face))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        match &self.sides {
+            Sides::Labels(_) => write!(f, "{} = [{}]", self.notation, faces),
+            _ => write!(f, "{} = [{}] = {}", self.notation, faces, self.total()),
+        }
+    }
+}
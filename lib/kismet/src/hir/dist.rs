@@ -0,0 +1,675 @@
+use std::{cmp::Ordering, collections::BTreeMap, fmt};
+
+use crate::{
+    ast::{OpEqs, OpExplode, OpKeep, OpReroll},
+    types::{Integer, Node},
+};
+
+use super::{
+    Bind, Block, Context, Dice, DieCompare, DieMod, Error, Exec, Instruction, Primitive, Query,
+    Sides, Stack, SymbolTable, Value, Var, MAX_DICE, MAX_EXPLODE, MAX_REROLL,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dist(Vec<(Primitive, f64)>);
+
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    pub sym: SymbolTable,
+    pub stack: Stack,
+    pub max_dice: usize,
+    pub max_explode: usize,
+    pub max_reroll: usize,
+}
+
+impl Default for Analysis {
+    fn default() -> Self {
+        Analysis {
+            sym: SymbolTable::default(),
+            stack: Stack::default(),
+            max_dice: MAX_DICE,
+            max_explode: MAX_EXPLODE,
+            max_reroll: MAX_REROLL,
+        }
+    }
+}
+
+impl From<&Context> for Analysis {
+    fn from(val: &Context) -> Self {
+        Analysis {
+            sym: val.sym.clone(),
+            stack: val.stack.clone(),
+            max_dice: val.max_dice,
+            max_explode: val.max_explode,
+            max_reroll: val.max_reroll,
+        }
+    }
+}
+
+impl Analysis {
+    pub fn bind(&mut self, kind: Bind, key: Var, val: Value) -> Result<(), Error> {
+        match key {
+            Var::Id(x) => {
+                self.sym.bind(kind, x, val)?;
+            }
+            Var::Local(x) => self.stack.set(x, val)?,
+        }
+        Ok(())
+    }
+
+    pub fn declare(&mut self, key: Var, val: Value) {
+        match key {
+            Var::Id(x) => {
+                self.sym.declare(x, val);
+            }
+            Var::Local(x) => self.stack.store(x, val),
+        }
+    }
+}
+
+impl Dist {
+    pub fn constant(val: Primitive) -> Self {
+        Dist(vec![(val, 1.)])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(Primitive, f64)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn single(&self) -> Option<&Primitive> {
+        match &self.0[..] {
+            [(val, _)] => Some(val),
+            _ => None,
+        }
+    }
+
+    pub fn map<F>(&self, mut f: F) -> Result<Dist, Error>
+    where
+        F: FnMut(&Primitive) -> Result<Primitive, Error>,
+    {
+        Ok(self
+            .0
+            .iter()
+            .map(|(val, p)| Ok((f(val)?, *p)))
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
+            .collect())
+    }
+
+    pub fn combine<F>(&self, rhs: &Dist, mut f: F) -> Result<Dist, Error>
+    where
+        F: FnMut(&Primitive, &Primitive) -> Result<Primitive, Error>,
+    {
+        Ok(self
+            .0
+            .iter()
+            .flat_map(|(l, p)| rhs.0.iter().map(move |(r, q)| (l, r, p * q)))
+            .map(|(l, r, p)| Ok((f(l, r)?, p)))
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
+            .collect())
+    }
+
+    pub fn bind<F>(&self, mut f: F) -> Result<Dist, Error>
+    where
+        F: FnMut(&Primitive) -> Result<Dist, Error>,
+    {
+        let mut acc = vec![];
+        for (val, p) in self.0.iter() {
+            acc.extend(f(val)?.0.into_iter().map(|(val, q)| (val, p * q)));
+        }
+        Ok(acc.into_iter().collect())
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        self.0.iter().map(|(val, p)| Some(number(val)? * p)).sum()
+    }
+
+    pub fn min(&self) -> Option<&Primitive> {
+        self.0.first().map(|x| &x.0)
+    }
+
+    pub fn max(&self) -> Option<&Primitive> {
+        self.0.last().map(|x| &x.0)
+    }
+
+    pub fn percentile(&self, q: f64) -> Option<&Primitive> {
+        let mut acc = 0.;
+        self.0
+            .iter()
+            .find(|(_, p)| {
+                acc += p;
+                acc >= q - 1e-12
+            })
+            .or_else(|| self.0.last())
+            .map(|x| &x.0)
+    }
+
+    pub fn variance(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        self.0
+            .iter()
+            .map(|(val, p)| Some((number(val)? - mean).powi(2) * p))
+            .sum()
+    }
+}
+
+fn number(val: &Primitive) -> Option<f64> {
+    match val {
+        Primitive::Integer(x) => Some(*x as f64),
+        Primitive::Float(x) => Some(*x as f64),
+        _ => None,
+    }
+}
+
+fn order(lhs: &Primitive, rhs: &Primitive) -> Ordering {
+    fn rank(val: &Primitive) -> u8 {
+        match val {
+            Primitive::Undefined => 0,
+            Primitive::Null => 1,
+            Primitive::Boolean(_) => 2,
+            Primitive::Integer(_) => 3,
+            Primitive::Float(_) => 4,
+            Primitive::String(_) => 5,
+        }
+    }
+
+    match (lhs, rhs, number(lhs), number(rhs)) {
+        (_, _, Some(l), Some(r)) => l.total_cmp(&r).then(rank(lhs).cmp(&rank(rhs))),
+        (Primitive::Boolean(l), Primitive::Boolean(r), _, _) => l.cmp(r),
+        (Primitive::String(l), Primitive::String(r), _, _) => l.cmp(r),
+        _ => rank(lhs).cmp(&rank(rhs)),
+    }
+}
+
+impl FromIterator<(Primitive, f64)> for Dist {
+    fn from_iter<T: IntoIterator<Item = (Primitive, f64)>>(iter: T) -> Self {
+        let mut val = iter.into_iter().filter(|x| x.1 > 0.).collect::<Vec<_>>();
+        val.sort_by(|l, r| order(&l.0, &r.0));
+        val.dedup_by(|next, acc| match acc.0 == next.0 {
+            true => {
+                acc.1 += next.1;
+                true
+            }
+            false => false,
+        });
+        Dist(val)
+    }
+}
+
+impl fmt::Display for Dist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self
+            .0
+            .iter()
+            .map(|(val, p)| format!("{}: {:.4}%", val, p * 100.))
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Exec<Analysis, Dist, Error> for Block {
+    fn exec(&self, i: &mut Analysis) -> Result<Dist, Error> {
+        fn block(x: &[Node<Instruction>], i: &mut Analysis) -> Result<Dist, Error> {
+            let mut last = Dist::constant(Primitive::default());
+            for (idx, val) in x.iter().enumerate() {
+                let assign = match &*val.data {
+                    Instruction::Assign(kind, key, val) => Some((*kind, Var::Id(key.clone()), val)),
+                    Instruction::Store(key, val) => Some((Bind::Rebind, Var::Local(*key), val)),
+                    _ => None,
+                };
+                if let Some((kind, key, val)) = assign {
+                    let val: Dist = val.exec(i)?;
+                    if let Some(x) = val.single() {
+                        i.bind(kind, key, Value::Primitive(x.clone()))?;
+                        last = val;
+                        continue;
+                    }
+                    let (sym, stack) = (i.sym.clone(), i.stack.clone());
+                    let rest = &x[idx + 1..];
+                    let val = val.bind(|val| {
+                        i.sym = sym.clone();
+                        i.stack = stack.clone();
+                        i.bind(kind, key.clone(), Value::Primitive(val.clone()))?;
+                        match rest.is_empty() {
+                            true => Ok(Dist::constant(val.clone())),
+                            false => block(rest, i),
+                        }
+                    });
+                    (i.sym, i.stack) = (sym, stack);
+                    return val;
+                }
+                last = val.exec(i)?;
+            }
+            Ok(last)
+        }
+
+        block(&self.0, i)
+    }
+}
+
+impl Exec<Analysis, Dist, Error> for Instruction {
+    fn exec(&self, i: &mut Analysis) -> Result<Dist, Error> {
+        match self {
+            Self::Value(x) | Self::Symbol(x) => match Primitive::try_from(x.clone()) {
+                Ok(x) => Ok(Dist::constant(x)),
+                Err(_) => Err(Error::TypeMismatch),
+            },
+            Self::Variable(key) => match Primitive::try_from(i.sym.get(key.clone())) {
+                Ok(x) => Ok(Dist::constant(x)),
+                Err(_) => Err(Error::TypeMismatch),
+            },
+            Self::Local(key) => match Primitive::try_from(i.stack.load(*key)) {
+                Ok(x) => Ok(Dist::constant(x)),
+                Err(_) => Err(Error::TypeMismatch),
+            },
+            Self::Action(_)
+            | Self::Destructure(_, _, _)
+            | Self::Range(_)
+            | Self::Loop(_)
+            | Self::Break(_, _)
+            | Self::Function(_)
+            | Self::Return(_)
+            | Self::Index(_, _)
+            | Self::Generator(_)
+            | Self::ListComprehension(_)
+            | Self::DictComprehension(_) => Err(Error::InvalidOp),
+            Self::Block(x) => {
+                i.sym.push();
+                let res = x.exec(i);
+                i.sym.pop();
+                res
+            }
+            Self::Assign(kind, key, val) => {
+                let val: Dist = val.exec(i)?;
+                match val.single() {
+                    Some(x) => {
+                        i.sym
+                            .bind(*kind, key.clone(), Value::Primitive(x.clone()))?;
+                        Ok(val)
+                    }
+                    None => Err(Error::InvalidOp),
+                }
+            }
+            Self::Store(key, val) => {
+                let val: Dist = val.exec(i)?;
+                match val.single() {
+                    Some(x) => {
+                        i.stack.set(*key, Value::Primitive(x.clone()))?;
+                        Ok(val)
+                    }
+                    None => Err(Error::InvalidOp),
+                }
+            }
+            Self::Dice(x) => x.exec(i),
+            Self::Arith(lhs, op, rhs) => {
+                let lhs: Dist = lhs.exec(i)?;
+                let rhs: Dist = rhs.exec(i)?;
+                lhs.combine(&rhs, |l, r| l.clone().arith(*op, r.clone()))
+            }
+            Self::Unary(op, rhs) => {
+                let rhs: Dist = rhs.exec(i)?;
+                rhs.map(|x| x.clone().unary(*op))
+            }
+            Self::And(lhs, rhs) | Self::Or(lhs, rhs) => {
+                let lhs: Dist = lhs.exec(i)?;
+                let short = matches!(self, Self::Or(_, _));
+                let rhs: Dist = match lhs.iter().any(|x| x.0.truthy() != short) {
+                    true => rhs.exec(i)?,
+                    false => Dist::constant(Primitive::default()),
+                };
+                lhs.bind(|x| match x.truthy() == short {
+                    true => Ok(Dist::constant(x.clone())),
+                    false => Ok(rhs.clone()),
+                })
+            }
+            Self::Not(val) => {
+                let val: Dist = val.exec(i)?;
+                val.map(|x| Ok(Primitive::Boolean(!x.truthy())))
+            }
+            Self::Compare(lhs, op, rhs) => {
+                let lhs: Dist = lhs.exec(i)?;
+                let rhs: Dist = rhs.exec(i)?;
+                lhs.combine(&rhs, |l, r| Ok(Primitive::Boolean(l.compare(*op, r)?)))
+            }
+            Self::CompareBound {
+                l_val,
+                l_op,
+                val,
+                r_op,
+                r_val,
+            } => {
+                let lhs: Dist = l_val.exec(i)?;
+                let val: Dist = val.exec(i)?;
+                let rhs: Dist = r_val.exec(i)?;
+                val.bind(|v| {
+                    lhs.combine(&rhs, |l, r| {
+                        Ok(Primitive::Boolean(
+                            l.compare(*l_op, v)? && v.compare(*r_op, r)?,
+                        ))
+                    })
+                })
+            }
+            Self::Call(val, args) => {
+                let f: Dist = val.exec(i)?;
+                match (f.single(), Query::builtin(val, args)) {
+                    (Some(Primitive::Undefined), Some(x)) => x?.exec(i),
+                    _ => Err(Error::InvalidOp),
+                }
+            }
+            Self::Branch(x) => x.exec(i),
+        }
+    }
+}
+
+type Pmf = BTreeMap<Integer, f64>;
+
+type Compare = Option<(OpEqs, Integer)>;
+
+enum Mod {
+    Keep(OpKeep, Integer),
+    Explode(OpExplode, Compare),
+    Reroll(OpReroll, Compare),
+    Success(OpEqs, Integer),
+    Failure(OpEqs, Integer),
+}
+
+impl Exec<Analysis, Dist, Error> for Dice {
+    fn exec(&self, i: &mut Analysis) -> Result<Dist, Error> {
+        fn constant(val: &Node<Instruction>, i: &mut Analysis) -> Result<Integer, Error> {
+            let val: Dist = val.exec(i)?;
+            match val.single() {
+                Some(Primitive::Integer(x)) => Ok(*x),
+                Some(_) => Err(Error::TypeMismatch),
+                None => Err(Error::InvalidOp),
+            }
+        }
+
+        fn compare(val: &Option<DieCompare>, i: &mut Analysis) -> Result<Compare, Error> {
+            val.as_ref()
+                .map(|x| Ok((x.0, constant(&x.1, i)?)))
+                .transpose()
+        }
+
+        let mut mods = vec![];
+        for x in self.mods.iter() {
+            mods.push(match &*x.data {
+                DieMod::Keep(op, val) => Mod::Keep(*op, constant(val, i)?),
+                DieMod::Explode(op, val) => Mod::Explode(*op, compare(val, i)?),
+                DieMod::Reroll(op, val) => Mod::Reroll(*op, compare(val, i)?),
+                DieMod::Success(val) => Mod::Success(val.0, constant(&val.1, i)?),
+                DieMod::Failure(val) => Mod::Failure(val.0, constant(&val.1, i)?),
+                DieMod::Crit(_, _) => continue,
+            });
+        }
+        let count: Dist = self.count.exec(i)?;
+        let sides = match &*self.sides.data {
+            Instruction::Value(x) => vec![(Sides::try_from(x.clone())?, 1.)],
+            _ => {
+                let val: Dist = self.sides.exec(i)?;
+                val.iter()
+                    .map(|(x, p)| Ok((Sides::try_from(Value::Primitive(x.clone()))?, *p)))
+                    .collect::<Result<_, Error>>()?
+            }
+        };
+        let mut acc = vec![];
+        for (sides, p) in sides.iter() {
+            let val = count.bind(|x| match x {
+                Primitive::Integer(x) => roll(*x, sides, &mods, i),
+                _ => Err(Error::TypeMismatch),
+            })?;
+            acc.extend(val.0.into_iter().map(|(x, q)| (x, p * q)));
+        }
+        Ok(acc.into_iter().collect())
+    }
+}
+
+fn roll(count: Integer, sides: &Sides, mods: &[Mod], i: &Analysis) -> Result<Dist, Error> {
+    if count > i.max_dice as Integer {
+        return Err(Error::DiceLimit);
+    }
+    let base = match sides {
+        Sides::Range(n) if *n >= 1 => (1..=*n).map(|x| (x, 1. / *n as f64)).collect::<Pmf>(),
+        Sides::Faces(x) if !x.is_empty() => x.iter().fold(Pmf::new(), |mut acc, val| {
+            *acc.entry(*val).or_default() += 1. / x.len() as f64;
+            acc
+        }),
+        _ => return Err(Error::InvalidOp),
+    };
+    let count = usize::try_from(count).map_err(|_| Error::InvalidOp)?;
+    let (min, max) = (sides.min(), sides.max());
+
+    let counting = mods
+        .iter()
+        .any(|x| matches!(x, Mod::Success(_, _) | Mod::Failure(_, _)));
+    let contrib = |face: Integer| match counting {
+        true => {
+            let success = mods
+                .iter()
+                .any(|x| matches!(x, Mod::Success(op, val) if op.compare(&face, val)));
+            let failure = mods
+                .iter()
+                .any(|x| matches!(x, Mod::Failure(op, val) if op.compare(&face, val)));
+            success as Integer - failure as Integer
+        }
+        false => face,
+    };
+
+    let mut first = base.clone();
+    for x in mods.iter() {
+        if let Mod::Reroll(op, val) = x {
+            let (cmp, val) = val.unwrap_or((OpEqs::EQ, min));
+            let depth = match op {
+                OpReroll::REROLL => i.max_reroll,
+                OpReroll::ONCE => 1,
+            };
+            first = reroll(&first, &base, |x| cmp.compare(&x, &val), depth);
+        }
+    }
+
+    let mut explode = mods.iter().filter_map(|x| match x {
+        Mod::Explode(op, val) => Some((*op, val.unwrap_or((OpEqs::EQ, max)))),
+        _ => None,
+    });
+    let mut keep = mods.iter().filter_map(|x| match x {
+        Mod::Keep(op, val) => Some((*op, *val)),
+        _ => None,
+    });
+    let (explode, keep) = match (explode.next(), explode.next(), keep.next(), keep.next()) {
+        (explode, None, keep, None) => (explode, keep),
+        _ => return Err(Error::InvalidOp),
+    };
+
+    let faces = match explode {
+        None => first,
+        Some((OpExplode::COMPOUND, (cmp, val))) => chain(
+            &first,
+            &base,
+            |x| x,
+            |x| x,
+            |x| cmp.compare(&x, &val),
+            i.max_explode,
+        ),
+        Some((op, (cmp, val))) => {
+            if keep.is_some() {
+                return Err(Error::NotAnalysable);
+            }
+            let penetrate = (op == OpExplode::PENETRATE) as Integer;
+            let unit = chain(
+                &first,
+                &base,
+                contrib,
+                |x| contrib(x - penetrate),
+                |x| cmp.compare(&x, &val),
+                i.max_explode,
+            );
+            return Ok(pmf(sum(&unit, count)));
+        }
+    };
+
+    Ok(pmf(match keep {
+        Some((op, n)) => {
+            let n = usize::try_from(n).map_err(|_| Error::InvalidOp)?;
+            let (highest, n) = match op {
+                OpKeep::KH => (true, n),
+                OpKeep::KL => (false, n),
+                OpKeep::DH => (false, count.saturating_sub(n)),
+                OpKeep::DL => (true, count.saturating_sub(n)),
+            };
+            keep_pmf(&faces, count, n, highest, contrib)
+        }
+        None => sum(
+            &faces.iter().fold(Pmf::new(), |mut acc, (x, p)| {
+                *acc.entry(contrib(*x)).or_default() += p;
+                acc
+            }),
+            count,
+        ),
+    }))
+}
+
+fn pmf(val: Pmf) -> Dist {
+    val.into_iter()
+        .map(|(x, p)| (Primitive::Integer(x), p))
+        .collect()
+}
+
+fn convolve(lhs: &Pmf, rhs: &Pmf) -> Pmf {
+    let mut acc = Pmf::new();
+    for (l, p) in lhs.iter() {
+        for (r, q) in rhs.iter() {
+            *acc.entry(l.saturating_add(*r)).or_default() += p * q;
+        }
+    }
+    acc
+}
+
+fn sum(unit: &Pmf, count: usize) -> Pmf {
+    let mut acc = Pmf::from([(0, 1.)]);
+    let mut unit = unit.clone();
+    let mut count = count;
+    while count > 0 {
+        if count & 1 == 1 {
+            acc = convolve(&acc, &unit);
+        }
+        count >>= 1;
+        if count > 0 {
+            unit = convolve(&unit, &unit);
+        }
+    }
+    acc
+}
+
+fn reroll<F>(first: &Pmf, base: &Pmf, cond: F, depth: usize) -> Pmf
+where
+    F: Fn(Integer) -> bool,
+{
+    if depth == 0 {
+        return first.clone();
+    }
+    let q_first = first
+        .iter()
+        .filter(|(x, _)| cond(**x))
+        .map(|(_, p)| p)
+        .sum::<f64>();
+    let q_base = base
+        .iter()
+        .filter(|(x, _)| cond(**x))
+        .map(|(_, p)| p)
+        .sum::<f64>();
+    let tail = (0..depth).map(|x| q_base.powi(x as i32)).sum::<f64>();
+    let exhausted = q_base.powi(depth as i32 - 1);
+    base.iter()
+        .map(|(x, p)| {
+            let val = match cond(*x) {
+                true => q_first * exhausted * p,
+                false => first.get(x).copied().unwrap_or_default() + q_first * tail * p,
+            };
+            (*x, val)
+        })
+        .filter(|(_, p)| *p > 0.)
+        .collect()
+}
+
+fn chain<H, T, C>(first: &Pmf, base: &Pmf, head: H, tail: T, cond: C, depth: usize) -> Pmf
+where
+    H: Fn(Integer) -> Integer,
+    T: Fn(Integer) -> Integer,
+    C: Fn(Integer) -> bool,
+{
+    fn step<F, C>(roll: &Pmf, rest: &Pmf, f: F, cond: C) -> Pmf
+    where
+        F: Fn(Integer) -> Integer,
+        C: Fn(Integer) -> bool,
+    {
+        let mut acc = Pmf::new();
+        for (x, p) in roll.iter() {
+            match cond(*x) {
+                true => {
+                    for (y, q) in rest.iter() {
+                        if p * q > 1e-18 {
+                            *acc.entry(f(*x).saturating_add(*y)).or_default() += p * q;
+                        }
+                    }
+                }
+                false => *acc.entry(f(*x)).or_default() += p,
+            }
+        }
+        acc
+    }
+
+    if depth == 0 {
+        return step(first, &Pmf::new(), head, |_| false);
+    }
+    let mut rest = step(base, &Pmf::new(), &tail, |_| false);
+    for _ in 1..depth {
+        rest = step(base, &rest, &tail, &cond);
+    }
+    step(first, &rest, head, cond)
+}
+
+fn keep_pmf<F>(faces: &Pmf, count: usize, keep: usize, highest: bool, contrib: F) -> Pmf
+where
+    F: Fn(Integer) -> Integer,
+{
+    fn binom(n: usize, k: usize) -> f64 {
+        (1..=k).fold(1., |acc, x| acc * (n - k + x) as f64 / x as f64)
+    }
+
+    let faces: Vec<_> = match highest {
+        true => faces.iter().rev().collect(),
+        false => faces.iter().collect(),
+    };
+    let mut state = BTreeMap::from([((0, 0), 1.)]);
+    for (face, p) in faces {
+        let mut next = BTreeMap::new();
+        for ((assigned, total), w) in state {
+            let rest = count - assigned;
+            for c in 0..=rest {
+                let kept = c.min(keep.saturating_sub(assigned)) as Integer;
+                let w = w * binom(rest, c) * p.powi(c as i32);
+                if w > 0. {
+                    let total = contrib(*face).saturating_mul(kept).saturating_add(total);
+                    *next.entry((assigned + c, total)).or_default() += w;
+                }
+            }
+        }
+        state = next;
+    }
+    state
+        .into_iter()
+        .filter(|((assigned, _), _)| *assigned == count)
+        .map(|((_, total), p)| (total, p))
+        .fold(Pmf::new(), |mut acc, (x, p)| {
+            *acc.entry(x).or_default() += p;
+            acc
+        })
+}
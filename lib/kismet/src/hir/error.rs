@@ -1,6 +1,11 @@
 use std::convert::Infallible;
 
-use crate::{ast, types::Node};
+use crate::{
+    ast::{self, Id},
+    types::Node,
+};
+
+use super::Value;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Error {
@@ -8,7 +13,17 @@ pub enum Error {
     Node(Node<Error>),
     Ast(ast::Error),
     TypeMismatch,
-    //InvalidOp,
+    InvalidOp,
+    NotAnalysable,
+    IterationLimit,
+    DiceLimit,
+    RecursionLimit,
+    OutOfBounds,
+    Overflow,
+    MissingKey,
+    Captured,
+    Break(Option<Id>, Value),
+    Return(Value),
 }
 
 impl From<Infallible> for Error {
@@ -1,7 +1,7 @@
 use crate::types::BaseNode;
 
 pub trait Exec<U, V, E> {
-    fn exec(&self, i: U) -> Result<V, E>;
+    fn exec(&self, i: &mut U) -> Result<V, E>;
 }
 
 impl<S, T, U, V, E> Exec<U, V, BaseNode<S, E>> for BaseNode<S, T>
@@ -11,7 +11,7 @@ where
     S: Clone,
     E: Clone,
 {
-    fn exec(&self, i: U) -> Result<V, BaseNode<S, E>> {
+    fn exec(&self, i: &mut U) -> Result<V, BaseNode<S, E>> {
         self.data
             .exec(i)
             .map_err(|x| match BaseNode::<S, E>::try_from(x.clone()) {
@@ -0,0 +1,86 @@
+use std::{fmt, mem, sync::Arc};
+
+use crate::types::Node;
+
+use super::{
+    Block, Collection, Context, Error, Exec, Instruction, Pattern, SymbolTable, Value, Var,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Function {
+    pub params: Vec<Param>,
+    pub block: Node<Block>,
+    pub captures: Vec<(usize, usize)>,
+    pub name: Option<Var>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Param {
+    Target(Pattern, Option<Node<Instruction>>),
+    Spread(Pattern),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Closure {
+    pub func: Arc<Function>,
+    pub env: SymbolTable,
+    pub slots: Vec<Value>,
+}
+
+impl Function {
+    fn call(&self, args: Vec<Value>, i: &mut Context) -> Result<Value, Error> {
+        let mut args = args.into_iter();
+        for param in &self.params {
+            let binds = match param {
+                Param::Target(tar, default) => {
+                    let val = match (args.next(), default) {
+                        (Some(x), _) => x,
+                        (None, Some(x)) => x.exec(i)?,
+                        (None, None) => return Err(Error::TypeMismatch),
+                    };
+                    tar.destructure(val)?
+                }
+                Param::Spread(tar) => {
+                    tar.destructure(Value::Collection(Collection::List(args.by_ref().collect())))?
+                }
+            };
+            for (key, val) in binds {
+                i.declare(key, val);
+            }
+        }
+        if args.next().is_some() {
+            return Err(Error::TypeMismatch);
+        }
+        match self.block.exec(i) {
+            Ok(x) => Ok(x),
+            Err(x) => match &*x.data {
+                Error::Return(val) => Ok(val.clone()),
+                Error::Break(_, _) => Err(Error::Node(Node::new(x.span, Error::InvalidOp))),
+                _ => Err(Error::Node(x)),
+            },
+        }
+    }
+}
+
+impl Closure {
+    pub fn call(&self, args: Vec<Value>, i: &mut Context) -> Result<Value, Error> {
+        i.enter()?;
+        let sym = mem::replace(&mut i.sym, self.env.clone());
+        i.sym.push();
+        i.stack.push_frame(self.slots.clone(), &self.func.captures);
+        if let Some(name) = &self.func.name {
+            i.declare(name.clone(), Value::Function(self.clone()));
+        }
+        let res = self.func.call(args, i);
+        i.stack.pop_frame();
+        i.sym = sym;
+        i.exit();
+        res
+    }
+}
+
+impl fmt::Display for Closure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "function")
+    }
+}
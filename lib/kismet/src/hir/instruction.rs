@@ -1,34 +1,142 @@
-use crate::{ast::Id, types::Node};
+use std::sync::Arc;
 
-use super::{Action, Block, Error, Exec, SymbolTable, SymbolTableResult, Value};
+use crate::{
+    ast::{Id, OpArith, OpEqs},
+    types::Node,
+};
+
+use super::{
+    Action, Args, Bind, Block, Branch, Closure, Comprehension, Context, Dice, DictItemComp, Error,
+    Exec, Function, ListItem, Loop, Pattern, Primitive, Query, RangeExpr, Value,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Instruction {
     Value(Value),
     Variable(Id),
+    Local(usize),
     Action(Action),
     Block(Block),
-    Assign(Id, Node<Instruction>),
+    Assign(Bind, Id, Node<Instruction>),
+    Destructure(Bind, Node<Pattern>, Node<Instruction>),
+    Store(usize, Node<Instruction>),
     Symbol(Value),
+    Dice(Dice),
+    Arith(Node<Instruction>, OpArith, Node<Instruction>),
+    Unary(OpArith, Node<Instruction>),
+    And(Node<Instruction>, Node<Instruction>),
+    Or(Node<Instruction>, Node<Instruction>),
+    Not(Node<Instruction>),
+    Compare(Node<Instruction>, OpEqs, Node<Instruction>),
+    CompareBound {
+        l_val: Node<Instruction>,
+        l_op: OpEqs,
+        val: Node<Instruction>,
+        r_op: OpEqs,
+        r_val: Node<Instruction>,
+    },
+    Range(RangeExpr),
+    Branch(Branch),
+    Loop(Loop),
+    Break(Option<Id>, Node<Instruction>),
+    Function(Arc<Function>),
+    Call(Node<Instruction>, Args),
+    Return(Node<Instruction>),
+    Index(Node<Instruction>, Node<Instruction>),
+    Generator(Arc<Comprehension<ListItem<Instruction>>>),
+    ListComprehension(Arc<Comprehension<ListItem<Instruction>>>),
+    DictComprehension(Arc<Comprehension<DictItemComp<Instruction>>>),
 }
 
-impl Exec<SymbolTable, (SymbolTable, Value), Error> for Instruction {
-    fn exec(&self, i: SymbolTable) -> SymbolTableResult {
+impl Exec<Context, Value, Error> for Instruction {
+    fn exec(&self, i: &mut Context) -> Result<Value, Error> {
         match self {
-            Self::Value(x) => Ok((i, x.clone())),
-            Self::Variable(key) => {
-                let mut i = i;
-                let val = i.get(key.clone());
-                Ok((i, val))
-            }
+            Self::Value(x) => Ok(x.clone()),
+            Self::Variable(key) => Ok(i.sym.get(key.clone())),
+            Self::Local(key) => Ok(i.stack.load(*key)),
             Self::Action(x) => x.exec(i),
-            Self::Block(x) => x.exec(i),
-            Self::Assign(key, val) => {
-                let (mut i, val) = val.exec(i)?;
-                i.set(key.clone(), val.clone());
-                Ok((i, val))
+            Self::Block(x) => {
+                i.sym.push();
+                let res = x.exec(i);
+                i.sym.pop();
+                res
+            }
+            Self::Assign(kind, key, val) => {
+                let val = val.exec(i)?;
+                i.sym.bind(*kind, key.clone(), val.clone())?;
+                Ok(val)
+            }
+            Self::Store(key, val) => {
+                let val = val.exec(i)?;
+                i.stack.set(*key, val.clone())?;
+                Ok(val)
+            }
+            Self::Destructure(kind, tar, val) => {
+                let val = val.exec(i)?;
+                for (key, x) in tar.destructure(val.clone())? {
+                    i.bind(*kind, key, x)?;
+                }
+                Ok(val)
+            }
+            Self::Symbol(x) => Ok(x.clone()),
+            Self::Dice(x) => x.exec(i),
+            Self::Arith(lhs, op, rhs) => lhs.exec(i)?.arith(*op, rhs.exec(i)?),
+            Self::Unary(op, rhs) => rhs.exec(i)?.unary(*op),
+            Self::And(lhs, rhs) => {
+                let lhs = lhs.exec(i)?;
+                match lhs.truthy() {
+                    true => Ok(rhs.exec(i)?),
+                    false => Ok(lhs),
+                }
+            }
+            Self::Or(lhs, rhs) => {
+                let lhs = lhs.exec(i)?;
+                match lhs.truthy() {
+                    true => Ok(lhs),
+                    false => Ok(rhs.exec(i)?),
+                }
+            }
+            Self::Not(val) => Ok(Value::Primitive(Primitive::Boolean(!val.exec(i)?.truthy()))),
+            Self::Compare(lhs, op, rhs) => Ok(Value::Primitive(Primitive::Boolean(
+                lhs.exec(i)?.compare(*op, &rhs.exec(i)?)?,
+            ))),
+            Self::CompareBound {
+                l_val,
+                l_op,
+                val,
+                r_op,
+                r_val,
+            } => {
+                let lhs = l_val.exec(i)?;
+                let val = val.exec(i)?;
+                let res = lhs.compare(*l_op, &val)? && val.compare(*r_op, &r_val.exec(i)?)?;
+                Ok(Value::Primitive(Primitive::Boolean(res)))
             }
-            Self::Symbol(x) => Ok((i, x.clone())),
+            Self::Range(x) => x.exec(i),
+            Self::Branch(x) => x.exec(i),
+            Self::Loop(x) => x.exec(i),
+            Self::Break(id, val) => Err(Error::Break(id.clone(), val.exec(i)?)),
+            Self::Function(x) => Ok(Value::Function(Closure {
+                func: x.clone(),
+                env: i.sym.capture(),
+                slots: i.stack.capture(&x.captures),
+            })),
+            Self::Call(val, args) => match val.exec(i)? {
+                Value::Function(x) => {
+                    let args = args.exec(i)?;
+                    x.call(args, i)
+                }
+                Value::Primitive(Primitive::Undefined) => match Query::builtin(val, args) {
+                    Some(x) => x?.exec(i),
+                    None => Err(Error::TypeMismatch),
+                },
+                _ => Err(Error::TypeMismatch),
+            },
+            Self::Return(val) => Err(Error::Return(val.exec(i)?)),
+            Self::Index(val, idx) => val.exec(i)?.index(&idx.exec(i)?),
+            Self::Generator(x) => Ok(x.generator(i)),
+            Self::ListComprehension(x) => x.list(i),
+            Self::DictComprehension(x) => x.dict(i),
         }
     }
 }
@@ -29,7 +29,7 @@ pub enum DictItemComp<T> {
 
 impl<T: fmt::Display> fmt::Display for ListItem<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &*self {
+        match self {
             Self::Expr(val) => write!(f, "{}", val),
             Self::Spread(val) => write!(f, "...{}", val),
         }
@@ -50,9 +50,26 @@ impl TryFrom<Node<ListItem<Expr>>> for Node<ListItem<Instruction>> {
     }
 }
 
+impl TryFrom<Node<DictItemComp<Expr>>> for Node<DictItemComp<Instruction>> {
+    type Error = Node<ast::Error>;
+
+    fn try_from(val: Node<DictItemComp<Expr>>) -> Result<Self, Self::Error> {
+        Node::try_convert(
+            |x| match x {
+                DictItemComp::DynKeyVal { key, val } => Ok(DictItemComp::DynKeyVal {
+                    key: Node::try_convert_from(key)?,
+                    val: Node::try_convert_from(val)?,
+                }),
+                DictItemComp::Spread(x) => Ok(DictItemComp::Spread(Node::try_convert_from(x)?)),
+            },
+            val,
+        )
+    }
+}
+
 impl<T: fmt::Display> fmt::Display for DictItem<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &*self {
+        match self {
             Self::KeyVal { key, val } => write!(f, "{}: {}", key, val),
             Self::DynKeyVal { key, val } => write!(f, "[{}]: {}", key, val),
             Self::Shorthand(val) => write!(f, "{}", val),
@@ -63,7 +80,7 @@ impl<T: fmt::Display> fmt::Display for DictItem<T> {
 
 impl<T: std::fmt::Display> fmt::Display for DictItemComp<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &*self {
+        match self {
             Self::DynKeyVal { key, val } => write!(f, "[{}]: {}", key, val),
             Self::Spread(val) => write!(f, "...{}", val),
         }
@@ -0,0 +1,78 @@
+use crate::{ast::Id, types::Node};
+
+use super::{Block, Context, Error, Exec, Instruction, Pattern, Value, Var};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Loop {
+    pub id: Option<Id>,
+    pub kind: LoopKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoopKind {
+    For {
+        tar: Node<Pattern>,
+        val: Node<Instruction>,
+        block: Node<Block>,
+    },
+    While {
+        val: Node<Instruction>,
+        block: Node<Block>,
+    },
+    Loop {
+        block: Node<Block>,
+    },
+}
+
+impl Loop {
+    fn step(
+        &self,
+        block: &Node<Block>,
+        binds: Vec<(Var, Value)>,
+        i: &mut Context,
+    ) -> Result<Option<Value>, Error> {
+        i.tick()?;
+        i.sym.push();
+        for (key, val) in binds {
+            i.declare(key, val);
+        }
+        let res = block.exec(i);
+        i.sym.pop();
+        match res {
+            Ok(_) => Ok(None),
+            Err(x) => match &*x.data {
+                Error::Break(id, val) if id.is_none() || *id == self.id => Ok(Some(val.clone())),
+                _ => Err(Error::Node(x)),
+            },
+        }
+    }
+}
+
+impl Exec<Context, Value, Error> for Loop {
+    fn exec(&self, i: &mut Context) -> Result<Value, Error> {
+        match &self.kind {
+            LoopKind::For { tar, val, block } => {
+                let mut cursor = val.exec(i)?.cursor()?;
+                while let Some(x) = cursor.next(i)? {
+                    let binds = tar.destructure(x)?;
+                    if let Some(x) = self.step(block, binds, i)? {
+                        return Ok(x);
+                    }
+                }
+            }
+            LoopKind::While { val, block } => {
+                while val.exec(i)?.truthy() {
+                    if let Some(x) = self.step(block, vec![], i)? {
+                        return Ok(x);
+                    }
+                }
+            }
+            LoopKind::Loop { block } => loop {
+                if let Some(x) = self.step(block, vec![], i)? {
+                    return Ok(x);
+                }
+            },
+        }
+        Ok(Value::default())
+    }
+}
@@ -1,22 +1,46 @@
 mod actions;
 mod block;
+mod branch;
 mod collection;
+mod comprehension;
+mod context;
+mod dice;
+mod dist;
 mod error;
 mod exec;
+mod function;
 mod instruction;
 mod item;
+mod loops;
+mod pattern;
 mod primitive;
+mod query;
+mod range;
+mod resolve;
+mod sim;
 mod symbol;
 mod value;
 
 pub use actions::*;
 pub use block::*;
+pub use branch::*;
 pub use collection::*;
+pub use comprehension::*;
+pub use context::*;
+pub use dice::*;
+pub use dist::*;
 pub use error::*;
 pub use exec::*;
+pub use function::*;
 pub use instruction::*;
 pub use item::*;
+pub use loops::*;
+pub use pattern::*;
 pub use primitive::*;
+pub use query::*;
+pub use range::*;
+pub use resolve::*;
+pub use sim::*;
 pub use symbol::*;
 pub use value::*;
 
@@ -28,3 +52,20 @@ where
 {
     Node::<Block>::try_convert_from(input).map_err(Node::<Error>::convert_from)
 }
+
+pub fn resolve(input: &Node<Block>) -> Node<Block> {
+    Resolver::default().resolve(input)
+}
+
+pub fn analyze(input: &Node<Block>) -> Result<Dist, Node<Error>> {
+    input.exec(&mut Analysis::default())
+}
+
+pub fn simulate(input: &Node<Block>, samples: usize, seed: u64) -> Result<Estimate, Node<Error>> {
+    Simulation {
+        samples,
+        seed,
+        ..Simulation::default()
+    }
+    .run(input)
+}
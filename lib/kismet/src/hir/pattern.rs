@@ -0,0 +1,130 @@
+use std::mem;
+
+use indexmap::IndexMap;
+
+use crate::ast::Id;
+
+use super::{Collection, Error, Value, Var};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+    Id(Id),
+    Local(usize),
+    Literal(Value),
+    Tuple(Vec<PatternItem>),
+    List(Vec<PatternItem>),
+    Dict(Vec<PatternDictItem>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatternItem {
+    Pattern(Pattern),
+    Spread(Pattern),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatternDictItem {
+    KeyVal(Id, Pattern),
+    Spread(Pattern),
+}
+
+type Bindings = Vec<(Var, Value)>;
+
+impl Pattern {
+    pub fn matches(&self, val: Value) -> Option<Bindings> {
+        let mut out = vec![];
+        self.bind(val, true, &mut out).ok()?;
+        Some(out)
+    }
+
+    pub fn destructure(&self, val: Value) -> Result<Bindings, Error> {
+        let mut out = vec![];
+        self.bind(val, false, &mut out)?;
+        Ok(out)
+    }
+
+    fn bind(&self, val: Value, strict: bool, out: &mut Bindings) -> Result<(), Error> {
+        match (self, val) {
+            (Self::Id(id), val) => {
+                out.push((Var::Id(id.clone()), val));
+                Ok(())
+            }
+            (Self::Local(idx), val) => {
+                out.push((Var::Local(*idx), val));
+                Ok(())
+            }
+            (Self::Literal(x), val) if x.equals(&val) => Ok(()),
+            (Self::Tuple(items) | Self::List(items), Value::Collection(Collection::Tuple(val)))
+                if !strict || matches!(self, Self::Tuple(_)) =>
+            {
+                bind_seq(items, val, Collection::Tuple, strict, out)
+            }
+            (Self::Tuple(items) | Self::List(items), Value::Collection(Collection::List(val)))
+                if !strict || matches!(self, Self::List(_)) =>
+            {
+                bind_seq(items, val, Collection::List, strict, out)
+            }
+            (Self::Dict(items), Value::Collection(Collection::Dict(val))) => {
+                bind_dict(items, val, strict, out)
+            }
+            _ => Err(Error::TypeMismatch),
+        }
+    }
+}
+
+fn bind_seq(
+    items: &[PatternItem],
+    mut val: Vec<Value>,
+    kind: fn(Vec<Value>) -> Collection,
+    strict: bool,
+    out: &mut Bindings,
+) -> Result<(), Error> {
+    let pos = items
+        .iter()
+        .position(|x| matches!(x, PatternItem::Spread(_)));
+    let len = items.len() - pos.is_some() as usize;
+    match pos {
+        Some(_) if val.len() < len => return Err(Error::TypeMismatch),
+        None if val.len() != len => return Err(Error::TypeMismatch),
+        _ => (),
+    }
+    let mut rest = pos.map(|pos| val.drain(pos..pos + val.len() - len).collect::<Vec<_>>());
+    let mut val = val.into_iter();
+    for item in items {
+        match item {
+            PatternItem::Pattern(x) => x.bind(val.next().unwrap_or_default(), strict, out)?,
+            PatternItem::Spread(x) => x.bind(
+                Value::Collection(kind(rest.take().unwrap_or_default())),
+                strict,
+                out,
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn bind_dict(
+    items: &[PatternDictItem],
+    mut val: IndexMap<Id, Value>,
+    strict: bool,
+    out: &mut Bindings,
+) -> Result<(), Error> {
+    for item in items {
+        if let PatternDictItem::KeyVal(key, x) = item {
+            match val.shift_remove(key) {
+                Some(val) => x.bind(val, strict, out)?,
+                None => return Err(Error::TypeMismatch),
+            }
+        }
+    }
+    for item in items {
+        if let PatternDictItem::Spread(x) = item {
+            x.bind(
+                Value::Collection(Collection::Dict(mem::take(&mut val))),
+                strict,
+                out,
+            )?;
+        }
+    }
+    Ok(())
+}
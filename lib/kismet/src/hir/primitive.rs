@@ -1,6 +1,11 @@
 use std::fmt;
 
-use crate::types::{fmt_float, Float, Integer};
+use crate::{
+    ast::{OpArith, OpEqs},
+    types::{fmt_float, Float, Integer, UInteger},
+};
+
+use super::Error;
 
 #[derive(Clone, Default, Debug, PartialEq)]
 pub enum Primitive {
@@ -15,7 +20,7 @@ pub enum Primitive {
 
 impl fmt::Display for Primitive {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &*self {
+        match self {
             Self::Boolean(x) => write!(f, "{}", x),
             Self::Integer(x) => write!(f, "{}", x),
             Self::Float(x) => fmt_float(f, x),
@@ -25,3 +30,101 @@ impl fmt::Display for Primitive {
         }
     }
 }
+
+impl Primitive {
+    pub fn truthy(&self) -> bool {
+        match self {
+            Self::Boolean(x) => *x,
+            Self::Integer(x) => *x != 0,
+            Self::Float(x) => *x != 0.,
+            Self::String(x) => !x.is_empty(),
+            Self::Null | Self::Undefined => false,
+        }
+    }
+
+    pub fn arith(self, op: OpArith, rhs: Primitive) -> Result<Primitive, Error> {
+        fn arith_float(lhs: Float, op: OpArith, rhs: Float) -> Primitive {
+            Primitive::Float(match op {
+                OpArith::ADD => lhs + rhs,
+                OpArith::SUB => lhs - rhs,
+                OpArith::MUL => lhs * rhs,
+                OpArith::DIV => lhs / rhs,
+                OpArith::IDIV => lhs.rem_euclid(rhs),
+                OpArith::MOD => lhs % rhs,
+                OpArith::POW => lhs.powf(rhs),
+            })
+        }
+
+        fn arith_int(lhs: Integer, op: OpArith, rhs: Integer) -> Primitive {
+            match match op {
+                OpArith::ADD => lhs.checked_add(rhs),
+                OpArith::SUB => lhs.checked_sub(rhs),
+                OpArith::MUL => lhs.checked_mul(rhs),
+                OpArith::DIV => return arith_float(lhs as Float, op, rhs as Float),
+                OpArith::IDIV => lhs.checked_div(rhs),
+                OpArith::MOD => lhs.checked_rem(rhs),
+                OpArith::POW => match UInteger::try_from(rhs) {
+                    Ok(rhs) => lhs.checked_pow(rhs),
+                    Err(_) => None,
+                },
+            } {
+                Some(x) => Primitive::Integer(x),
+                None => arith_float(lhs as Float, op, rhs as Float),
+            }
+        }
+
+        match (self, rhs) {
+            (Primitive::Integer(Integer::MIN), Primitive::Integer(-1)) if op == OpArith::IDIV => {
+                Err(Error::Overflow)
+            }
+            (Primitive::Integer(lhs), Primitive::Integer(rhs)) => Ok(arith_int(lhs, op, rhs)),
+            (Primitive::Float(lhs), Primitive::Float(rhs)) => Ok(arith_float(lhs, op, rhs)),
+            (Primitive::Integer(lhs), Primitive::Float(rhs)) => match (op, lhs) {
+                (OpArith::POW, 2) => Ok(Primitive::Float(rhs.exp2())),
+                _ => Ok(arith_float(lhs as Float, op, rhs)),
+            },
+            (Primitive::Float(lhs), Primitive::Integer(rhs)) => match op {
+                OpArith::POW => Ok(Primitive::Float(lhs.powi(rhs))),
+                _ => Ok(arith_float(lhs, op, rhs as Float)),
+            },
+            (Primitive::String(lhs), Primitive::String(rhs)) => match op {
+                OpArith::ADD => Ok(Primitive::String(lhs + &rhs)),
+                _ => Err(Error::InvalidOp),
+            },
+            _ => Err(Error::TypeMismatch),
+        }
+    }
+
+    pub fn unary(self, op: OpArith) -> Result<Primitive, Error> {
+        match (op, self) {
+            (OpArith::ADD, val @ (Primitive::Integer(_) | Primitive::Float(_))) => Ok(val),
+            (OpArith::SUB, Primitive::Integer(val)) => Ok(match val.checked_neg() {
+                Some(val) => Primitive::Integer(val),
+                None => Primitive::Float(-(val as Float)),
+            }),
+            (OpArith::SUB, Primitive::Float(val)) => Ok(Primitive::Float(-val)),
+            (_, Primitive::Integer(_) | Primitive::Float(_)) => Err(Error::InvalidOp),
+            _ => Err(Error::TypeMismatch),
+        }
+    }
+
+    pub fn compare(&self, op: OpEqs, rhs: &Primitive) -> Result<bool, Error> {
+        match (self, rhs) {
+            (Primitive::Integer(lhs), Primitive::Integer(rhs)) => Ok(op.compare(lhs, rhs)),
+            (Primitive::Float(lhs), Primitive::Float(rhs)) => Ok(op.compare(lhs, rhs)),
+            (Primitive::Integer(lhs), Primitive::Float(rhs)) => {
+                Ok(op.compare(&(*lhs as Float), rhs))
+            }
+            (Primitive::Float(lhs), Primitive::Integer(rhs)) => {
+                Ok(op.compare(lhs, &(*rhs as Float)))
+            }
+            (Primitive::String(lhs), Primitive::String(rhs)) => Ok(op.compare(lhs, rhs)),
+            (Primitive::Boolean(lhs), Primitive::Boolean(rhs)) => Ok(op.compare(lhs, rhs)),
+            (lhs, rhs) => match op {
+                OpEqs::EQ => Ok(lhs == rhs),
+                OpEqs::NE => Ok(lhs != rhs),
+                _ => Err(Error::TypeMismatch),
+            },
+        }
+    }
+}
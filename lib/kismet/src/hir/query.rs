@@ -0,0 +1,95 @@
+use crate::types::{Float, Node};
+
+use super::{Analysis, Args, Context, Dist, Error, Exec, Instruction, Primitive, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Query {
+    Prob(Node<Instruction>),
+    Mean(Node<Instruction>),
+    Variance(Node<Instruction>),
+    Percentile(Node<Instruction>, Node<Instruction>),
+    Min(Node<Instruction>),
+    Max(Node<Instruction>),
+}
+
+impl Query {
+    pub fn builtin(val: &Node<Instruction>, args: &Args) -> Option<Result<Query, Error>> {
+        let Instruction::Variable(id) = &*val.data else {
+            return None;
+        };
+        Some(Ok(match (id.as_str(), &args.0[..]) {
+            ("P", [x]) => Query::Prob(x.clone()),
+            ("E", [x]) => Query::Mean(x.clone()),
+            ("Var", [x]) => Query::Variance(x.clone()),
+            ("percentile", [x, pct]) => Query::Percentile(x.clone(), pct.clone()),
+            ("min", [x]) => Query::Min(x.clone()),
+            ("max", [x]) => Query::Max(x.clone()),
+            ("P" | "E" | "Var" | "percentile" | "min" | "max", _) => {
+                return Some(Err(Error::InvalidOp))
+            }
+            _ => return None,
+        }))
+    }
+
+    pub fn eval(&self, i: &mut Analysis) -> Result<Primitive, Error> {
+        match self {
+            Self::Prob(val) => {
+                let val: Dist = val.exec(i)?;
+                val.iter()
+                    .map(|(x, p)| match x {
+                        Primitive::Boolean(x) => Ok(*x as u8 as f64 * p),
+                        _ => Err(Error::TypeMismatch),
+                    })
+                    .sum::<Result<f64, Error>>()
+                    .map(|x| Primitive::Float(x as Float))
+            }
+            Self::Mean(val) => {
+                let val: Dist = val.exec(i)?;
+                val.mean()
+                    .map(|x| Primitive::Float(x as Float))
+                    .ok_or(Error::TypeMismatch)
+            }
+            Self::Variance(val) => {
+                let val: Dist = val.exec(i)?;
+                val.variance()
+                    .map(|x| Primitive::Float(x as Float))
+                    .ok_or(Error::TypeMismatch)
+            }
+            Self::Percentile(val, pct) => {
+                let val: Dist = val.exec(i)?;
+                let pct: Dist = pct.exec(i)?;
+                let pct = match pct.single() {
+                    Some(Primitive::Integer(x)) => *x as f64,
+                    Some(Primitive::Float(x)) => *x as f64,
+                    Some(_) => return Err(Error::TypeMismatch),
+                    None => return Err(Error::InvalidOp),
+                };
+                if !(0. ..=100.).contains(&pct) {
+                    return Err(Error::InvalidOp);
+                }
+                val.percentile(pct / 100.).cloned().ok_or(Error::InvalidOp)
+            }
+            Self::Min(val) => {
+                let val: Dist = val.exec(i)?;
+                val.min().cloned().ok_or(Error::InvalidOp)
+            }
+            Self::Max(val) => {
+                let val: Dist = val.exec(i)?;
+                val.max().cloned().ok_or(Error::InvalidOp)
+            }
+        }
+    }
+}
+
+impl Exec<Context, Value, Error> for Query {
+    fn exec(&self, i: &mut Context) -> Result<Value, Error> {
+        let mut analysis = Analysis::from(&*i);
+        Ok(Value::Primitive(self.eval(&mut analysis)?))
+    }
+}
+
+impl Exec<Analysis, Dist, Error> for Query {
+    fn exec(&self, i: &mut Analysis) -> Result<Dist, Error> {
+        Ok(Dist::constant(self.eval(i)?))
+    }
+}
@@ -0,0 +1,97 @@
+use std::{fmt, iter, ops};
+
+use crate::types::{Integer, Node};
+
+use super::{Context, Error, Exec, Instruction, Primitive, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub start: Option<Integer>,
+    pub end: Option<Integer>,
+    pub inclusive: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeExpr {
+    pub start: Option<Node<Instruction>>,
+    pub end: Option<Node<Instruction>>,
+    pub inclusive: bool,
+}
+
+impl Range {
+    pub fn iter(&self) -> impl Iterator<Item = Integer> {
+        let (end, inclusive) = (self.end, self.inclusive);
+        iter::successors(Some(self.start.unwrap_or(0)), |x| x.checked_add(1)).take_while(move |x| {
+            match end {
+                Some(end) if inclusive => *x <= end,
+                Some(end) => *x < end,
+                None => true,
+            }
+        })
+    }
+
+    pub fn len(&self) -> Option<usize> {
+        let start = self.start.unwrap_or(0) as i64;
+        let end = self.end? as i64 + self.inclusive as i64;
+        Some((end - start).max(0) as usize)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    pub fn contains(&self, val: Integer) -> bool {
+        self.start.is_none_or(|x| x <= val)
+            && self.end.is_none_or(|x| match self.inclusive {
+                true => val <= x,
+                false => val < x,
+            })
+    }
+
+    pub fn resolve(&self, len: usize) -> ops::Range<usize> {
+        let idx = |x: Integer| match x < 0 {
+            true => len.saturating_sub(x.unsigned_abs() as usize),
+            false => (x as usize).min(len),
+        };
+        let start = self.start.map_or(0, idx);
+        let end = match self.end {
+            Some(x) if self.inclusive => match x.checked_add(1) {
+                Some(0) | None => len,
+                Some(x) => idx(x),
+            },
+            Some(x) => idx(x),
+            None => len,
+        };
+        start..end.max(start)
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start) = self.start {
+            write!(f, "{}", start)?;
+        }
+        write!(f, "{}", if self.inclusive { "..=" } else { ".." })?;
+        if let Some(end) = self.end {
+            write!(f, "{}", end)?;
+        }
+        Ok(())
+    }
+}
+
+impl Exec<Context, Value, Error> for RangeExpr {
+    fn exec(&self, i: &mut Context) -> Result<Value, Error> {
+        let mut bound = |x: &Option<Node<Instruction>>| match x {
+            Some(x) => match Primitive::try_from(x.exec(i)?) {
+                Ok(Primitive::Integer(x)) => Ok(Some(x)),
+                _ => Err(Error::TypeMismatch),
+            },
+            None => Ok(None),
+        };
+        Ok(Value::Range(Range {
+            start: bound(&self.start)?,
+            end: bound(&self.end)?,
+            inclusive: self.inclusive,
+        }))
+    }
+}
@@ -0,0 +1,326 @@
+use std::{mem, sync::Arc};
+
+use crate::{ast::Id, types::Node};
+
+use super::{
+    Action, Bind, Block, Branch, CompIter, Comprehension, Dice, DictItem, DictItemComp, DieCompare,
+    DieMod, Function, Instruction, ListItem, LoopKind, Param, Pattern, PatternDictItem,
+    PatternItem, SymbolIdx, Value, Var,
+};
+
+struct Frame {
+    idx: SymbolIdx,
+    scopes: Vec<Vec<(Id, Option<usize>)>>,
+    captures: Vec<(usize, usize)>,
+}
+
+#[derive(Default)]
+pub struct Resolver {
+    frames: Vec<Frame>,
+}
+
+impl Frame {
+    fn new() -> Self {
+        Frame {
+            idx: SymbolIdx::default(),
+            scopes: vec![vec![]],
+            captures: vec![],
+        }
+    }
+}
+
+impl Resolver {
+    pub fn resolve(&mut self, input: &Node<Block>) -> Node<Block> {
+        let mut out = input.clone();
+        self.frames.push(Frame::new());
+        self.block(&mut out.data);
+        self.frames.pop();
+        out
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("resolver has no frame")
+    }
+
+    fn push(&mut self) {
+        self.frame().scopes.push(vec![]);
+    }
+
+    fn pop(&mut self) {
+        let frame = self.frame();
+        if let Some(scope) = frame.scopes.pop() {
+            for (key, prev) in scope.into_iter().rev() {
+                frame.idx.restore(key, prev);
+            }
+        }
+    }
+
+    fn scoped(&mut self, f: impl FnOnce(&mut Self)) {
+        self.push();
+        f(self);
+        self.pop();
+    }
+
+    fn declare(&mut self, key: Id) -> usize {
+        let frame = self.frame();
+        let (idx, prev) = frame.idx.insert(key.clone());
+        if let Some(scope) = frame.scopes.last_mut() {
+            scope.push((key, prev));
+        }
+        idx
+    }
+
+    fn lookup(&mut self, key: &Id) -> Option<usize> {
+        self.find(self.frames.len().checked_sub(1)?, key)
+    }
+
+    fn find(&mut self, depth: usize, key: &Id) -> Option<usize> {
+        if let Some(x) = self.frames[depth].idx.find(key) {
+            return Some(x);
+        }
+        let outer = self.find(depth.checked_sub(1)?, key)?;
+        let frame = &mut self.frames[depth];
+        let inner = frame.idx.get(key.clone());
+        frame.captures.push((outer, inner));
+        Some(inner)
+    }
+
+    fn bind(&mut self, kind: Bind, key: Id) -> usize {
+        match kind {
+            Bind::Declare => self.declare(key),
+            Bind::Rebind => match self.lookup(&key) {
+                Some(x) => x,
+                None => self.declare(key),
+            },
+        }
+    }
+
+    fn closure(&mut self, f: impl FnOnce(&mut Self)) -> Vec<(usize, usize)> {
+        self.frames.push(Frame::new());
+        f(self);
+        self.frames.pop().map_or(vec![], |x| x.captures)
+    }
+
+    fn block(&mut self, val: &mut Block) {
+        for x in &mut val.0 {
+            self.instruction(&mut x.data);
+        }
+    }
+
+    fn pattern(&mut self, kind: Bind, val: &mut Pattern) {
+        match val {
+            Pattern::Id(key) => *val = Pattern::Local(self.bind(kind, key.clone())),
+            Pattern::Local(_) | Pattern::Literal(_) => {}
+            Pattern::Tuple(x) | Pattern::List(x) => {
+                for x in x {
+                    match x {
+                        PatternItem::Pattern(x) | PatternItem::Spread(x) => self.pattern(kind, x),
+                    }
+                }
+            }
+            Pattern::Dict(x) => {
+                for x in x {
+                    match x {
+                        PatternDictItem::KeyVal(_, x) | PatternDictItem::Spread(x) => {
+                            self.pattern(kind, x)
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn instruction(&mut self, val: &mut Instruction) {
+        match val {
+            Instruction::Value(_) | Instruction::Symbol(_) | Instruction::Local(_) => {}
+            Instruction::Variable(key) => {
+                if let Some(x) = self.lookup(key) {
+                    *val = Instruction::Local(x);
+                }
+            }
+            Instruction::Action(x) => self.action(x),
+            Instruction::Block(x) => self.scoped(|s| s.block(x)),
+            Instruction::Assign(kind, key, rhs) => {
+                self.instruction(&mut rhs.data);
+                let idx = self.bind(*kind, key.clone());
+                let span = rhs.span;
+                let rhs = mem::replace(rhs, Node::new(span, Instruction::Value(Value::default())));
+                *val = Instruction::Store(idx, rhs);
+            }
+            Instruction::Destructure(kind, tar, rhs) => {
+                self.instruction(&mut rhs.data);
+                self.pattern(*kind, &mut tar.data);
+            }
+            Instruction::Store(_, x)
+            | Instruction::Unary(_, x)
+            | Instruction::Not(x)
+            | Instruction::Break(_, x)
+            | Instruction::Return(x) => self.instruction(&mut x.data),
+            Instruction::Dice(x) => self.dice(x),
+            Instruction::Arith(lhs, _, rhs)
+            | Instruction::And(lhs, rhs)
+            | Instruction::Or(lhs, rhs)
+            | Instruction::Compare(lhs, _, rhs)
+            | Instruction::Index(lhs, rhs) => {
+                self.instruction(&mut lhs.data);
+                self.instruction(&mut rhs.data);
+            }
+            Instruction::CompareBound {
+                l_val, val, r_val, ..
+            } => {
+                self.instruction(&mut l_val.data);
+                self.instruction(&mut val.data);
+                self.instruction(&mut r_val.data);
+            }
+            Instruction::Range(x) => {
+                for x in [&mut x.start, &mut x.end].into_iter().flatten() {
+                    self.instruction(&mut x.data);
+                }
+            }
+            Instruction::Branch(x) => self.branch(x),
+            Instruction::Loop(x) => match &mut x.kind {
+                LoopKind::For { tar, val, block } => {
+                    self.instruction(&mut val.data);
+                    self.scoped(|s| {
+                        s.pattern(Bind::Declare, &mut tar.data);
+                        s.block(&mut block.data);
+                    });
+                }
+                LoopKind::While { val, block } => {
+                    self.instruction(&mut val.data);
+                    self.scoped(|s| s.block(&mut block.data));
+                }
+                LoopKind::Loop { block } => self.scoped(|s| s.block(&mut block.data)),
+            },
+            Instruction::Function(x) => self.function(Arc::make_mut(x)),
+            Instruction::Call(x, args) => {
+                self.instruction(&mut x.data);
+                for x in &mut args.0 {
+                    self.instruction(&mut x.data);
+                }
+            }
+            Instruction::Generator(x) | Instruction::ListComprehension(x) => {
+                self.comprehension(Arc::make_mut(x), |s, val| s.list_item(val))
+            }
+            Instruction::DictComprehension(x) => {
+                self.comprehension(Arc::make_mut(x), |s, val| match val {
+                    DictItemComp::DynKeyVal { key, val } => {
+                        s.instruction(&mut key.data);
+                        s.instruction(&mut val.data);
+                    }
+                    DictItemComp::Spread(x) => s.instruction(&mut x.data),
+                })
+            }
+        }
+    }
+
+    fn list_item(&mut self, val: &mut ListItem<Instruction>) {
+        match val {
+            ListItem::Expr(x) | ListItem::Spread(x) => self.instruction(x),
+        }
+    }
+
+    fn action(&mut self, val: &mut Action) {
+        match val {
+            Action::Tuple(x) | Action::ListDisplay(x) => {
+                for x in x {
+                    self.list_item(&mut x.data);
+                }
+            }
+            Action::DictDisplay(x) => {
+                for x in x {
+                    match &mut *x.data {
+                        DictItem::KeyVal { val, .. } => self.instruction(&mut val.data),
+                        DictItem::DynKeyVal { key, val } => {
+                            self.instruction(&mut key.data);
+                            self.instruction(&mut val.data);
+                        }
+                        DictItem::Shorthand(key) => {
+                            if let Some(idx) = self.lookup(key) {
+                                *x.data = DictItem::KeyVal {
+                                    key: Node::new(x.span, key.clone()),
+                                    val: Node::new(x.span, Instruction::Local(idx)),
+                                };
+                            }
+                        }
+                        DictItem::Spread(x) => self.instruction(&mut x.data),
+                    }
+                }
+            }
+        }
+    }
+
+    fn dice(&mut self, val: &mut Dice) {
+        self.instruction(&mut val.count.data);
+        self.instruction(&mut val.sides.data);
+        for x in &mut val.mods {
+            match &mut *x.data {
+                DieMod::Keep(_, x)
+                | DieMod::Explode(_, Some(DieCompare(_, x)))
+                | DieMod::Reroll(_, Some(DieCompare(_, x)))
+                | DieMod::Success(DieCompare(_, x))
+                | DieMod::Failure(DieCompare(_, x))
+                | DieMod::Crit(_, Some(DieCompare(_, x))) => self.instruction(&mut x.data),
+                DieMod::Explode(_, None) | DieMod::Reroll(_, None) | DieMod::Crit(_, None) => {}
+            }
+        }
+    }
+
+    fn branch(&mut self, val: &mut Branch) {
+        match val {
+            Branch::If {
+                val,
+                t_block,
+                f_block,
+            } => {
+                self.instruction(&mut val.data);
+                self.scoped(|s| s.block(&mut t_block.data));
+                self.scoped(|s| s.block(&mut f_block.data));
+            }
+            Branch::Match { val, arms } => {
+                self.instruction(&mut val.data);
+                for arm in arms {
+                    self.scoped(|s| {
+                        s.pattern(Bind::Declare, &mut arm.tar.data);
+                        s.block(&mut arm.block.data);
+                    });
+                }
+            }
+        }
+    }
+
+    fn function(&mut self, val: &mut Function) {
+        val.captures = self.closure(|s| {
+            if let Some(Var::Id(key)) = &val.name {
+                val.name = Some(Var::Local(s.declare(key.clone())));
+            }
+            for param in &mut val.params {
+                match param {
+                    Param::Target(tar, default) => {
+                        if let Some(x) = default {
+                            s.instruction(&mut x.data);
+                        }
+                        s.pattern(Bind::Declare, tar);
+                    }
+                    Param::Spread(tar) => s.pattern(Bind::Declare, tar),
+                }
+            }
+            s.block(&mut val.block.data);
+        });
+    }
+
+    fn comprehension<T>(&mut self, val: &mut Comprehension<T>, f: impl FnOnce(&mut Self, &mut T)) {
+        val.captures = self.closure(|s| {
+            for x in &mut val.iter {
+                match &mut *x.data {
+                    CompIter::For { tar, val } => {
+                        s.instruction(&mut val.data);
+                        s.pattern(Bind::Declare, &mut tar.data);
+                    }
+                    CompIter::If(x) => s.instruction(&mut x.data),
+                }
+            }
+            f(s, &mut val.val.data);
+        });
+    }
+}
@@ -0,0 +1,130 @@
+use std::thread;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::types::Node;
+
+use super::{
+    resolve, Block, Context, Dist, Error, Exec, Primitive, Stack, SymbolTable, MAX_DEPTH, MAX_DICE,
+    MAX_EXPLODE, MAX_ITER, MAX_REROLL,
+};
+
+const CHUNK: usize = 4096;
+
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    pub samples: usize,
+    pub seed: u64,
+    pub threads: usize,
+    pub max_dice: usize,
+    pub max_explode: usize,
+    pub max_reroll: usize,
+    pub max_iter: usize,
+    pub max_depth: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Estimate {
+    pub dist: Dist,
+    pub samples: usize,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Simulation {
+            samples: 10_000,
+            seed: 0,
+            threads: thread::available_parallelism().map_or(1, |x| x.get()),
+            max_dice: MAX_DICE,
+            max_explode: MAX_EXPLODE,
+            max_reroll: MAX_REROLL,
+            max_iter: MAX_ITER,
+            max_depth: MAX_DEPTH,
+        }
+    }
+}
+
+impl Simulation {
+    pub fn run(&self, input: &Node<Block>) -> Result<Estimate, Node<Error>> {
+        let input = &resolve(input);
+        let chunks = self.samples.div_ceil(CHUNK);
+        let threads = self.threads.clamp(1, chunks.max(1));
+        let mut acc = thread::scope(|s| {
+            let handles = (0..threads)
+                .map(|t| {
+                    s.spawn(move || {
+                        (t..chunks)
+                            .step_by(threads)
+                            .map(|c| (c, self.chunk(input, c)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|x| x.join().expect("simulation thread panicked"))
+                .collect::<Vec<_>>()
+        });
+        acc.sort_by_key(|x| x.0);
+        let acc = acc
+            .into_iter()
+            .map(|x| x.1)
+            .collect::<Result<Vec<_>, _>>()?;
+        let n = self.samples as f64;
+        Ok(Estimate {
+            dist: acc
+                .iter()
+                .flat_map(|x| x.iter())
+                .map(|(val, count)| (val.clone(), count / n))
+                .collect(),
+            samples: self.samples,
+        })
+    }
+
+    fn chunk(&self, input: &Node<Block>, idx: usize) -> Result<Dist, Node<Error>> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        rng.set_stream(idx as u64);
+        let mut i = Context::with_rng(rng);
+        i.max_dice = self.max_dice;
+        i.max_explode = self.max_explode;
+        i.max_reroll = self.max_reroll;
+        i.max_iter = self.max_iter;
+        i.max_depth = self.max_depth;
+        let len = CHUNK.min(self.samples - idx * CHUNK);
+        (0..len)
+            .map(|_| {
+                i.sym = SymbolTable::default();
+                i.stack = Stack::default();
+                match Primitive::try_from(input.exec(&mut i)?) {
+                    Ok(x) => Ok((x, 1.)),
+                    Err(_) => Err(Node::new(input.span, Error::TypeMismatch)),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Dist::from_iter)
+    }
+}
+
+impl Estimate {
+    pub fn prob(&self, val: &Primitive) -> f64 {
+        self.dist.iter().find(|x| x.0 == *val).map_or(0., |x| x.1)
+    }
+
+    pub fn interval(&self, val: &Primitive, z: f64) -> (f64, f64) {
+        let n = self.samples as f64;
+        let p = self.prob(val);
+        let z2 = z * z;
+        let center = (p + z2 / (2. * n)) / (1. + z2 / n);
+        let margin = z / (1. + z2 / n) * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt();
+        ((center - margin).max(0.), (center + margin).min(1.))
+    }
+
+    pub fn mean_interval(&self, z: f64) -> Option<(f64, f64)> {
+        let n = self.samples as f64;
+        let mean = self.dist.mean()?;
+        let var = self.dist.variance()? * n / (n - 1.).max(1.);
+        let margin = z * (var / n).sqrt();
+        Some((mean - margin, mean + margin))
+    }
+}
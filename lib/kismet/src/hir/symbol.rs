@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 use crate::ast::Id;
@@ -15,23 +16,107 @@ pub struct SymbolIdx {
 
 impl SymbolIdx {
     pub fn get(&mut self, key: Id) -> usize {
-        let len = self.map.len();
-        *self.map.entry(key).or_insert(len)
+        match self.find(&key) {
+            Some(x) => x,
+            None => self.insert(key).0,
+        }
+    }
+
+    pub fn find(&self, key: &Id) -> Option<usize> {
+        self.map.get(key).copied()
+    }
+
+    pub fn insert(&mut self, key: Id) -> (usize, Option<usize>) {
+        let idx = self.len;
+        self.len += 1;
+        (idx, self.map.insert(key, idx))
+    }
+
+    pub fn restore(&mut self, key: Id, prev: Option<usize>) {
+        match prev {
+            Some(x) => self.map.insert(key, x),
+            None => self.map.remove(&key),
+        };
     }
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
-pub struct SymbolTable(HashMap<Id, Value>);
+#[derive(Clone, Debug, PartialEq)]
+pub enum Var {
+    Id(Id),
+    Local(usize),
+}
 
-pub type SymbolTableResult = Result<(SymbolTable, Value), Error>;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bind {
+    Declare,
+    Rebind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SymbolTable {
+    scopes: Vec<Arc<HashMap<Id, Value>>>,
+    captured: usize,
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self {
+            scopes: vec![Arc::default()],
+            captured: 0,
+        }
+    }
+}
 
 impl SymbolTable {
-    pub fn get(&mut self, key: Id) -> Value {
-        self.0.entry(key).or_default().clone()
+    pub fn get(&self, key: Id) -> Value {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|x| x.get(&key))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, key: Id, val: Value) -> Result<Option<Value>, Error> {
+        match self.scopes.iter().rposition(|x| x.contains_key(&key)) {
+            Some(idx) if idx < self.captured => Err(Error::Captured),
+            Some(idx) => Ok(Arc::make_mut(&mut self.scopes[idx]).insert(key, val)),
+            None => Ok(self.declare(key, val)),
+        }
     }
 
-    pub fn set(&mut self, key: Id, val: Value) -> Option<Value> {
-        self.0.insert(key, val)
+    pub fn declare(&mut self, key: Id, val: Value) -> Option<Value> {
+        match self.scopes.last_mut() {
+            Some(x) => Arc::make_mut(x).insert(key, val),
+            None => {
+                self.scopes.push(Arc::new(HashMap::from([(key, val)])));
+                None
+            }
+        }
+    }
+
+    pub fn bind(&mut self, kind: Bind, key: Id, val: Value) -> Result<Option<Value>, Error> {
+        match kind {
+            Bind::Declare => Ok(self.declare(key, val)),
+            Bind::Rebind => self.set(key, val),
+        }
+    }
+
+    pub fn push(&mut self) {
+        self.scopes.push(Arc::default())
+    }
+
+    pub fn pop(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+
+    pub fn capture(&self) -> Self {
+        Self {
+            scopes: self.scopes.clone(),
+            captured: self.scopes.len(),
+        }
     }
 }
 
@@ -41,6 +126,7 @@ impl SymbolTable {
 pub struct Stack {
     val: Vec<Value>,
     pos: Vec<usize>,
+    captured: Vec<Vec<usize>>,
 }
 
 impl Stack {
@@ -48,12 +134,49 @@ impl Stack {
         *self.pos.last().unwrap_or(&0)
     }
 
-    pub fn push_frame(&mut self) {
-        self.pos.push(self.val.len())
+    pub fn push_frame(&mut self, val: Vec<Value>, captures: &[(usize, usize)]) {
+        self.pos.push(self.val.len());
+        self.val.extend(val);
+        self.captured.push(captures.iter().map(|x| x.1).collect());
+    }
+
+    pub fn pop_frame(&mut self) -> Vec<Value> {
+        self.captured.pop();
+        match self.pos.pop() {
+            Some(pos) => self.val.split_off(pos),
+            None => vec![],
+        }
+    }
+
+    pub fn load(&self, idx: usize) -> Value {
+        self.get(idx).cloned().unwrap_or_default()
+    }
+
+    pub fn store(&mut self, idx: usize, val: Value) {
+        let idx = self.pos() + idx;
+        if idx >= self.val.len() {
+            self.val.resize(idx + 1, Value::default());
+        }
+        self.val[idx] = val;
+    }
+
+    pub fn set(&mut self, idx: usize, val: Value) -> Result<(), Error> {
+        match self.captured.last() {
+            Some(x) if x.contains(&idx) => Err(Error::Captured),
+            _ => {
+                self.store(idx, val);
+                Ok(())
+            }
+        }
     }
 
-    pub fn pop_frame(&mut self) -> Option<usize> {
-        self.pos.pop()
+    pub fn capture(&self, val: &[(usize, usize)]) -> Vec<Value> {
+        let len = val.iter().map(|x| x.1 + 1).max().unwrap_or(0);
+        let mut vec = vec![Value::default(); len];
+        for (outer, inner) in val {
+            vec[*inner] = self.load(*outer);
+        }
+        vec
     }
 }
 
@@ -1,11 +1,20 @@
 use std::fmt;
 
-use super::{Collection, Primitive};
+use crate::{
+    ast::{Id, OpArith, OpEqs},
+    types::Integer,
+};
+
+use super::{Closure, Collection, Error, Generator, Primitive, Range, Roll};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Value {
     Primitive(Primitive),
     Collection(Collection),
+    Roll(Roll),
+    Range(Range),
+    Function(Closure),
+    Generator(Generator),
     Error,
 }
 
@@ -15,6 +24,167 @@ impl Default for Value {
     }
 }
 
+impl Value {
+    pub fn truthy(&self) -> bool {
+        match self {
+            Self::Primitive(x) => x.truthy(),
+            Self::Collection(Collection::Tuple(x) | Collection::List(x)) => !x.is_empty(),
+            Self::Collection(Collection::Dict(x)) => !x.is_empty(),
+            Self::Roll(x) => x.total().truthy(),
+            Self::Range(x) => !x.is_empty(),
+            Self::Function(_) | Self::Generator(_) => true,
+            Self::Error => false,
+        }
+    }
+
+    pub fn arith(self, op: OpArith, rhs: Value) -> Result<Value, Error> {
+        match (Primitive::try_from(self), Primitive::try_from(rhs)) {
+            (Ok(lhs), Ok(rhs)) => Ok(Value::Primitive(lhs.arith(op, rhs)?)),
+            _ => Err(Error::TypeMismatch),
+        }
+    }
+
+    pub fn unary(self, op: OpArith) -> Result<Value, Error> {
+        match Primitive::try_from(self) {
+            Ok(val) => Ok(Value::Primitive(val.unary(op)?)),
+            Err(_) => Err(Error::TypeMismatch),
+        }
+    }
+
+    pub fn iterate(self) -> Result<Box<dyn Iterator<Item = Value>>, Error> {
+        match self {
+            Self::Collection(Collection::Tuple(x) | Collection::List(x)) => {
+                Ok(Box::new(x.into_iter()))
+            }
+            Self::Collection(Collection::Dict(x)) => Ok(Box::new(x.into_iter().map(|(k, v)| {
+                Value::Collection(Collection::Tuple(vec![
+                    Value::Primitive(Primitive::String(k.0)),
+                    v,
+                ]))
+            }))),
+            Self::Range(x) => Ok(Box::new(
+                x.iter().map(|x| Value::Primitive(Primitive::Integer(x))),
+            )),
+            _ => Err(Error::TypeMismatch),
+        }
+    }
+
+    pub fn index(self, idx: &Value) -> Result<Value, Error> {
+        match (self, idx) {
+            (
+                Self::Collection(Collection::Dict(mut x)),
+                Self::Primitive(Primitive::String(key)),
+            ) => x.swap_remove(&Id(key.clone())).ok_or(Error::MissingKey),
+            (Self::Collection(Collection::Tuple(mut x)), Self::Range(idx)) => {
+                let idx = idx.resolve(x.len());
+                Ok(Self::Collection(Collection::Tuple(x.drain(idx).collect())))
+            }
+            (Self::Collection(Collection::List(mut x)), Self::Range(idx)) => {
+                let idx = idx.resolve(x.len());
+                Ok(Self::Collection(Collection::List(x.drain(idx).collect())))
+            }
+            (Self::Collection(Collection::Tuple(mut x) | Collection::List(mut x)), idx) => {
+                let idx = position(idx, x.len())?;
+                Ok(x.swap_remove(idx))
+            }
+            (Self::Primitive(Primitive::String(x)), Self::Range(idx)) => {
+                let x = x.chars().collect::<Vec<_>>();
+                let idx = idx.resolve(x.len());
+                Ok(Self::Primitive(Primitive::String(x[idx].iter().collect())))
+            }
+            (Self::Primitive(Primitive::String(x)), idx) => {
+                let x = x.chars().collect::<Vec<_>>();
+                let idx = position(idx, x.len())?;
+                Ok(Self::Primitive(Primitive::String(x[idx].to_string())))
+            }
+            (Self::Range(x), idx) => {
+                let start = x.start.unwrap_or(0);
+                let bound = |pos: usize| {
+                    Integer::try_from(pos)
+                        .ok()
+                        .and_then(|pos| start.checked_add(pos))
+                        .ok_or(Error::OutOfBounds)
+                };
+                match (x.len(), idx) {
+                    (Some(len), Self::Range(idx)) => {
+                        let idx = idx.resolve(len);
+                        Ok(Self::Range(Range {
+                            start: Some(bound(idx.start)?),
+                            end: Some(bound(idx.end)?),
+                            inclusive: false,
+                        }))
+                    }
+                    (None, Self::Range(_)) => Err(Error::InvalidOp),
+                    (Some(len), idx) => Ok(Self::Primitive(Primitive::Integer(bound(position(
+                        idx, len,
+                    )?)?))),
+                    (None, idx) => match usize::try_from(integer(idx)?) {
+                        Ok(pos) => Ok(Self::Primitive(Primitive::Integer(bound(pos)?))),
+                        Err(_) => Err(Error::OutOfBounds),
+                    },
+                }
+            }
+            _ => Err(Error::TypeMismatch),
+        }
+    }
+
+    pub fn compare(&self, op: OpEqs, rhs: &Value) -> Result<bool, Error> {
+        match op {
+            OpEqs::EQ => Ok(self.equals(rhs)),
+            OpEqs::NE => Ok(!self.equals(rhs)),
+            _ => match (
+                Primitive::try_from(self.clone()),
+                Primitive::try_from(rhs.clone()),
+            ) {
+                (Ok(lhs), Ok(rhs)) => lhs.compare(op, &rhs),
+                _ => Err(Error::TypeMismatch),
+            },
+        }
+    }
+
+    pub fn equals(&self, rhs: &Value) -> bool {
+        match (self, rhs) {
+            (
+                Self::Collection(Collection::Tuple(lhs)),
+                Self::Collection(Collection::Tuple(rhs)),
+            )
+            | (Self::Collection(Collection::List(lhs)), Self::Collection(Collection::List(rhs))) => {
+                lhs.len() == rhs.len() && lhs.iter().zip(rhs.iter()).all(|(l, r)| l.equals(r))
+            }
+            (Self::Range(lhs), Self::Range(rhs)) => lhs == rhs,
+            (Self::Collection(Collection::Dict(lhs)), Self::Collection(Collection::Dict(rhs))) => {
+                lhs.len() == rhs.len()
+                    && lhs
+                        .iter()
+                        .all(|(k, l)| rhs.get(k).is_some_and(|r| l.equals(r)))
+            }
+            _ => match (
+                Primitive::try_from(self.clone()),
+                Primitive::try_from(rhs.clone()),
+            ) {
+                (Ok(lhs), Ok(rhs)) => lhs.compare(OpEqs::EQ, &rhs).unwrap_or(false),
+                _ => false,
+            },
+        }
+    }
+}
+
+fn integer(val: &Value) -> Result<Integer, Error> {
+    match Primitive::try_from(val.clone()) {
+        Ok(Primitive::Integer(x)) => Ok(x),
+        _ => Err(Error::TypeMismatch),
+    }
+}
+
+fn position(idx: &Value, len: usize) -> Result<usize, Error> {
+    let idx = integer(idx)?;
+    let pos = match idx < 0 {
+        true => len.checked_sub(idx.unsigned_abs() as usize),
+        false => Some(idx as usize),
+    };
+    pos.filter(|x| *x < len).ok_or(Error::OutOfBounds)
+}
+
 impl From<Primitive> for Value {
     fn from(val: Primitive) -> Self {
         Value::Primitive(val)
@@ -28,6 +198,10 @@ impl TryFrom<Value> for Primitive {
         match val {
             Value::Primitive(x) => Ok(x),
             Value::Collection(_) => Err(()),
+            Value::Roll(x) => Ok(x.total()),
+            Value::Range(_) => Err(()),
+            Value::Function(_) => Err(()),
+            Value::Generator(_) => Err(()),
             Value::Error => Err(()),
         }
     }
@@ -35,9 +209,13 @@ impl TryFrom<Value> for Primitive {
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &*self {
+        match self {
             Self::Primitive(x) => write!(f, "{}", x),
             Self::Collection(x) => write!(f, "{}", x),
+            Self::Roll(x) => write!(f, "{}", x),
+            Self::Range(x) => write!(f, "{}", x),
+            Self::Function(x) => write!(f, "{}", x),
+            Self::Generator(x) => write!(f, "{}", x),
             Self::Error => write!(f, "error"),
         }
     }
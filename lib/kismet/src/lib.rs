@@ -3,5 +3,5 @@ pub mod hir;
 pub mod parser;
 pub mod types;
 
-pub use hir::compile;
+pub use hir::{analyze, compile, resolve, simulate};
 pub use parser::parse;
@@ -1,6 +1,6 @@
 use nom::{
     branch::alt,
-    combinator::opt,
+    combinator::{opt, peek},
     multi::{many0, separated_list1},
     sequence::preceded,
     Err,
@@ -8,8 +8,8 @@ use nom::{
 
 use crate::{
     ast::{
-        Atom, CompIter, DictItem, DictItemComp, Id, ListItem, TargetDictItem, TargetExpr,
-        TargetKind, TargetListItem,
+        Atom, CompIter, DictItem, DictItemComp, Expr, Id, ListItem, Stmt, Target, TargetDictItem,
+        TargetExpr, TargetKind, TargetListItem,
     },
     types::{Node, ONode, Span},
 };
@@ -261,13 +261,13 @@ pub fn target_tuple_result<T>(
                     };
                     let (i, rhs) = close(i)?;
 
-                    return Err(Err::Failure(ONode::new(
+                    Err(Err::Failure(ONode::new(
                         span,
                         Error::Convert(
                             i,
                             ConvertKind::TargetKindExpr(Node::new(lhs_span + rhs.span, kind(vals))),
                         ),
-                    )));
+                    )))
                 }
                 _ => Err(Err::Failure(val)),
             }
@@ -315,13 +315,13 @@ pub fn target_list_result<T>(
                     };
                     let (i, rhs) = close(i)?;
 
-                    return Err(Err::Failure(ONode::new(
+                    Err(Err::Failure(ONode::new(
                         span,
                         Error::Convert(
                             i,
                             ConvertKind::TargetKindExpr(Node::new(lhs_span + rhs.span, kind(vals))),
                         ),
-                    )));
+                    )))
                 }
                 _ => Err(Err::Failure(val)),
             }
@@ -431,11 +431,29 @@ pub fn brace(i: Input) -> KResult<Node<Atom>> {
     Ok((i, Node::new(lhs.span + rhs.span, Atom::DictDisplay(vals))))
 }
 
+fn expr_block(i: Input, head: Node<Expr>, span: Span) -> KResult<Node<DictItem>> {
+    let (i, vals) = stmt_block0(i)?;
+    let mut vals = match vals {
+        Some(val) => *val.data,
+        None => vec![],
+    };
+    let (i, rhs) = token_tag(Token::RBRACE)(i)?;
+    let head_span = head.span;
+    vals.insert(0, head);
+    Err(Err::Failure(ONode::new(
+        span,
+        Error::Convert(
+            i,
+            ConvertKind::ExprBlock(Node::new(head_span + rhs.span, vals)),
+        ),
+    )))
+}
+
 pub fn dict_item(i: Input) -> KResult<Node<DictItem>> {
     let (i, lhs) = opt(token_tag(Token::LBRACKET))(i)?;
     if let Some(lhs) = lhs {
         let (i, key) = assignment_expr(i)?;
-        let (i, _) = token_tag(Token::RBRACE)(i)?;
+        let (i, _) = token_tag(Token::RBRACKET)(i)?;
         let (i, _) = token_tag(Token::COLON)(i)?;
         let (i, val) = assignment_expr(i)?;
         return Ok((
@@ -491,24 +509,13 @@ pub fn dict_item(i: Input) -> KResult<Node<DictItem>> {
     let key_span = key.span;
 
     let (i, delim) = opt(token_tag(Token::DELIM))(i)?;
-    match delim {
-        Some(delim) => {
-            let (i, vals) = stmt_block0(i)?;
-            let mut vals = match vals {
-                Some(val) => *val.data,
-                None => vec![],
-            };
-            let (i, rhs) = token_tag(Token::RBRACE)(i)?;
-            vals.insert(0, key);
-            return Err(Err::Failure(ONode::new(
-                delim.span,
-                Error::Convert(
-                    i,
-                    ConvertKind::ExprBlock(Node::new(key_span + rhs.span, vals)),
-                ),
-            )));
+    if let Some(delim) = delim {
+        return expr_block(i, key, delim.span);
+    }
+    if let (None, Expr::Assign(_, _)) = (&lhs, &*key.data) {
+        if let (i, Some(_)) = opt(peek(token_tag(Token::RBRACE)))(i)? {
+            return expr_block(i, key, key_span);
         }
-        None => (),
     }
     let (i, val) = match lhs {
         Some(_) => (i, None),
@@ -516,6 +523,25 @@ pub fn dict_item(i: Input) -> KResult<Node<DictItem>> {
     };
     let (i, ass) = opt(preceded(token_tag(Token::ASSIGN), expr))(i)?;
 
+    if let (None, None, Some(rhs)) = (&lhs, &val, &ass) {
+        let (i, delim) = opt(alt((
+            token_tag(Token::DELIM),
+            peek(token_tag(Token::RBRACE)),
+        )))(i)?;
+        if let Some(delim) = delim {
+            if let Ok(tar) = Node::<Target>::try_from(key.clone()) {
+                let head = Node::new(
+                    key_span + rhs.span,
+                    Expr::Stmt(Stmt::Assign {
+                        tar,
+                        val: rhs.clone(),
+                    }),
+                );
+                return expr_block(i, head, delim.span);
+            }
+        }
+    }
+
     if let (Some(lhs), None) = (&lhs, &ass) {
         return Ok((i, Node::new(lhs.span + key.span, DictItem::Spread(key))));
     }
@@ -523,7 +549,7 @@ pub fn dict_item(i: Input) -> KResult<Node<DictItem>> {
         Ok(key) => match ass {
             Some(ass) => {
                 let span = Span::option_ref(&lhs) + key.span + ass.span;
-                return Err(Err::Failure(ONode::new(
+                Err(Err::Failure(ONode::new(
                     ass.span,
                     Error::Convert(
                         i,
@@ -568,7 +594,7 @@ pub fn dict_item(i: Input) -> KResult<Node<DictItem>> {
                             },
                         )),
                     ),
-                )));
+                )))
             }
             None => match val {
                 Some(val) => Ok((
@@ -623,7 +649,7 @@ pub fn target_dict_result<T>(
                     };
                     let (i, rhs) = close(i)?;
 
-                    return Err(Err::Failure(ONode::new(
+                    Err(Err::Failure(ONode::new(
                         span,
                         Error::Convert(
                             i,
@@ -632,7 +658,7 @@ pub fn target_dict_result<T>(
                                 TargetKind::TargetDict(vals),
                             )),
                         ),
-                    )));
+                    )))
                 }
                 _ => Err(Err::Failure(val)),
             }
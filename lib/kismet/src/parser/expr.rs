@@ -222,7 +222,7 @@ pub fn lambda_expr(i: Input) -> KResult<Node<Expr>> {
                         )))
                     }
                 };
-                let (i, block) = stmt_enclosure(i)?;
+                let (i, block) = lambda_body(i)?;
                 return Ok((
                     i,
                     Node::new(lhs.span + block.span, Expr::Function { args, block }),
@@ -240,7 +240,7 @@ pub fn lambda_expr(i: Input) -> KResult<Node<Expr>> {
             .map_err(|_| Err::Failure(ONode::new(lhs_span, Error::Error(ErrorKind::Grammar))))?,
         None => return Ok((i, lhs)),
     };
-    let (i, block) = stmt_enclosure(i)?;
+    let (i, block) = lambda_body(i)?;
     let args = match *tar.data {
         Target(TargetKind::Id(x)) => Node::new(
             lhs_span,
@@ -269,3 +269,10 @@ pub fn lambda_expr(i: Input) -> KResult<Node<Expr>> {
         Node::new(lhs_span + block.span, Expr::Function { args, block }),
     ))
 }
+
+pub fn lambda_body(i: Input) -> KResult<Node<ExprEnclosure>> {
+    alt((
+        stmt_enclosure,
+        map(expr, |x| Node::new(x.span, ExprEnclosure(vec![x]))),
+    ))(i)
+}
@@ -1,14 +1,19 @@
 use nom::{
-    combinator::opt,
-    sequence::{preceded, tuple},
+    branch::alt,
+    combinator::{map, opt, peek},
+    sequence::{preceded, terminated, tuple},
     Err,
 };
 
-use crate::ast::{Expr, Op, OpArith, OpEqs, OpRange, Primary, Range};
+use crate::ast::{
+    Atom, DieCompare, DieMod, Expr, Op, OpArith, OpCrit, OpEqs, OpExplode, OpKeep, OpRange,
+    OpReroll, Primary, Range,
+};
 use crate::types::{Node, ONode, Span};
 
 use super::{
-    numeric_literal, primary, token_action, token_tag, Error, ErrorKind, Input, KResult, Token,
+    brackets, numeric_literal, parens, primary, token_action, token_tag, DieToken, Error,
+    ErrorKind, Input, KResult, Token,
 };
 
 pub fn or_test(i: Input) -> KResult<Node<Expr>> {
@@ -165,8 +170,20 @@ pub fn u_expr(i: Input) -> KResult<Node<Expr>> {
 }
 
 pub fn coefficient(i: Input) -> KResult<Node<Expr>> {
-    let (i, lhs) = opt(numeric_literal)(i)?;
-    let (i, rhs) = opt(die)(i)?;
+    let (i, lhs) = opt(alt((
+        numeric_literal,
+        terminated(
+            parens,
+            peek(alt((
+                map(token_tag(Token::DIE), |_| ()),
+                map(fudge(true), |_| ()),
+            ))),
+        ),
+    )))(i)?;
+    let (i, rhs) = match lhs.is_some() && token_tag(Token::LBRACE)(i).is_ok() {
+        true => (i, None),
+        false => opt(die(lhs.is_some()))(i)?,
+    };
     match (lhs, rhs) {
         (Some(lhs), Some(rhs)) => Ok((
             i,
@@ -181,11 +198,212 @@ pub fn coefficient(i: Input) -> KResult<Node<Expr>> {
     }
 }
 
-pub fn die(i: Input) -> KResult<Node<Expr>> {
-    let (i, die_val) = opt(tuple((token_tag(Token::DIE), numeric_literal)))(i)?;
-    match die_val {
-        Some((op, rhs)) => Ok((i, Node::new(op.span + rhs.span, Expr::Op(Op::Die(rhs))))),
-        None => expr_node(i),
+pub fn die<'input>(counted: bool) -> impl Fn(Input<'input>) -> KResult<'input, Node<Expr>> {
+    move |i| {
+        let (i, die_val) = opt(tuple((
+            token_tag(Token::DIE),
+            alt((numeric_literal, parens, brackets)),
+        )))(i)?;
+        match die_val {
+            Some((op, rhs)) => {
+                let (i, mods) = die_mods(rhs.span.end)(i)?;
+                Ok((
+                    i,
+                    Node::new(
+                        op.span + rhs.span + Span::reduce(&mods),
+                        Expr::Op(Op::Die(rhs, mods)),
+                    ),
+                ))
+            }
+            None => alt((fudge(counted), expr_node))(i),
+        }
+    }
+}
+
+pub fn fudge<'input>(counted: bool) -> impl Fn(Input<'input>) -> KResult<'input, Node<Expr>> {
+    move |i| {
+        let error = || Err::Error(ONode::new(Span::get0(i), Error::Error(ErrorKind::Grammar)));
+        let op = match i.first() {
+            Some(x) if counted || *x.data == Token::Id("dF".into()) => {
+                match DieToken::lex(x).as_deref() {
+                    Some([x, ..]) if *x.data == DieToken::FUDGE => x.span,
+                    _ => return Err(error()),
+                }
+            }
+            _ => return Err(error()),
+        };
+        match die_mods_from(i, op.start, 1) {
+            (0, _) => Err(error()),
+            (consumed, mods) => Ok((
+                &i[consumed..],
+                Node::new(op + Span::reduce(&mods), Expr::Op(Op::Fudge(op, mods))),
+            )),
+        }
+    }
+}
+
+pub fn die_mods<'input>(
+    end: usize,
+) -> impl Fn(Input<'input>) -> KResult<'input, Vec<Node<DieMod>>> {
+    move |i| {
+        let (consumed, mods) = die_mods_from(i, end, 0);
+        Ok((&i[consumed..], mods))
+    }
+}
+
+fn die_mods_from(i: Input, end: usize, skip: usize) -> (usize, Vec<Node<DieMod>>) {
+    let mut end = end;
+    let mut tokens = vec![];
+    for (idx, x) in i.iter().enumerate() {
+        if x.span.start != end {
+            break;
+        }
+        match DieToken::lex(x) {
+            Some(val) => tokens.extend(val.into_iter().map(|val| (idx, val))),
+            None => break,
+        }
+        end = x.span.end;
+    }
+
+    let mut mods = vec![];
+    let (mut pos, mut len, mut consumed) = (skip, 0, 0);
+    if pos > 0 && tokens.get(pos).map(|x| x.0) != Some(tokens[pos - 1].0) {
+        consumed = tokens[pos - 1].0 + 1;
+    }
+    while let Some((n, val)) = die_mod(&tokens[pos..]) {
+        pos += n;
+        mods.push(val);
+        match tokens.get(pos) {
+            Some((idx, _)) if *idx == tokens[pos - 1].0 => (),
+            _ => (len, consumed) = (mods.len(), tokens[pos - 1].0 + 1),
+        }
+    }
+    mods.truncate(len);
+    (consumed, mods)
+}
+
+fn die_mod(i: &[(usize, Node<DieToken>)]) -> Option<(usize, Node<DieMod>)> {
+    let (_, head) = i.first()?;
+    let number = |n: usize| match i.get(n) {
+        Some((_, x)) => match *x.data {
+            DieToken::Number(val) => Some(Node::new(x.span, Atom::Integer(val))),
+            _ => None,
+        },
+        None => None,
+    };
+    match *head.data {
+        DieToken::KH | DieToken::KL | DieToken::DH | DieToken::DL => {
+            let op = Node::new(
+                head.span,
+                match *head.data {
+                    DieToken::KH => OpKeep::KH,
+                    DieToken::KL => OpKeep::KL,
+                    DieToken::DH => OpKeep::DH,
+                    _ => OpKeep::DL,
+                },
+            );
+            match number(1) {
+                Some(val) => Some((
+                    2,
+                    Node::new(op.span + val.span, DieMod::Keep(op, Some(val))),
+                )),
+                None => Some((1, Node::new(op.span, DieMod::Keep(op, None)))),
+            }
+        }
+        DieToken::BANG => {
+            let (n, op) = match i.get(1).map(|(_, x)| (x.span, &*x.data)) {
+                Some((span, DieToken::BANG)) => {
+                    (2, Node::new(head.span + span, OpExplode::COMPOUND))
+                }
+                Some((span, DieToken::P)) => (2, Node::new(head.span + span, OpExplode::PENETRATE)),
+                _ => (1, Node::new(head.span, OpExplode::EXPLODE)),
+            };
+            match die_compare(&i[n..]) {
+                Some((m, val)) => Some((
+                    n + m,
+                    Node::new(op.span + val.span, DieMod::Explode(op, Some(val))),
+                )),
+                None => Some((n, Node::new(op.span, DieMod::Explode(op, None)))),
+            }
+        }
+        DieToken::R | DieToken::RO => {
+            let op = Node::new(
+                head.span,
+                match *head.data {
+                    DieToken::R => OpReroll::REROLL,
+                    _ => OpReroll::ONCE,
+                },
+            );
+            match die_compare(&i[1..]) {
+                Some((n, val)) => Some((
+                    n + 1,
+                    Node::new(op.span + val.span, DieMod::Reroll(op, Some(val))),
+                )),
+                None => Some((1, Node::new(op.span, DieMod::Reroll(op, None)))),
+            }
+        }
+        DieToken::NE => match die_compare(&i[1..])? {
+            (n, val) if val.op.is_none() => {
+                let op = Node::new(head.span, OpExplode::EXPLODE);
+                Some((
+                    n + 1,
+                    Node::new(op.span + val.span, DieMod::Explode(op, Some(val))),
+                ))
+            }
+            _ => None,
+        },
+        DieToken::S => {
+            let (n, val) = die_compare(&i[1..])?;
+            Some((n + 1, Node::new(head.span + val.span, DieMod::Success(val))))
+        }
+        DieToken::F => {
+            let (n, val) = die_compare(&i[1..])?;
+            Some((n + 1, Node::new(head.span + val.span, DieMod::Failure(val))))
+        }
+        DieToken::CS | DieToken::CF => {
+            let op = Node::new(
+                head.span,
+                match *head.data {
+                    DieToken::CS => OpCrit::SUCCESS,
+                    _ => OpCrit::FAILURE,
+                },
+            );
+            match die_compare(&i[1..]) {
+                Some((n, val)) => Some((
+                    n + 1,
+                    Node::new(op.span + val.span, DieMod::Crit(op, Some(val))),
+                )),
+                None => Some((1, Node::new(op.span, DieMod::Crit(op, None)))),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn die_compare(i: &[(usize, Node<DieToken>)]) -> Option<(usize, Node<DieCompare>)> {
+    let (_, head) = i.first()?;
+    let op = match *head.data {
+        DieToken::EQ => Some(Node::new(head.span, OpEqs::EQ)),
+        DieToken::LT => Some(Node::new(head.span, OpEqs::LT)),
+        DieToken::LE => Some(Node::new(head.span, OpEqs::LE)),
+        DieToken::GT => Some(Node::new(head.span, OpEqs::GT)),
+        DieToken::GE => Some(Node::new(head.span, OpEqs::GE)),
+        _ => None,
+    };
+    let n = if op.is_some() { 1 } else { 0 };
+    let (_, val) = i.get(n)?;
+    match *val.data {
+        DieToken::Number(x) => Some((
+            n + 1,
+            Node::new(
+                Span::option(&op) + val.span,
+                DieCompare {
+                    op,
+                    val: Node::new(val.span, Atom::Integer(x)),
+                },
+            ),
+        )),
+        _ => None,
     }
 }
 
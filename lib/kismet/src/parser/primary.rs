@@ -19,11 +19,9 @@ pub fn primary(i: Input) -> KResult<Node<Primary>> {
             i,
             Node::convert(
                 |x| {
-                    Primary::Atom(Atom::Float({
-                        let x = x as Float;
-                        let mag = x.log10().ceil();
-                        x / mag
-                    }))
+                    Primary::Atom(Atom::Float(
+                        x as Float / (10 as Float).powi(x.to_string().len() as i32),
+                    ))
                 },
                 lhs,
             ),
@@ -84,12 +84,22 @@ pub fn break_stmt(i: Input) -> KResult<Node<Expr>> {
 
 pub fn assignment_stmt(i: Input) -> KResult<Node<Expr>> {
     let (i, lhs) = expr(i)?;
-    let (i, op) = opt(token_tag(Token::ASSIGN))(i)?;
+    let (i, op) = opt(alt((token_tag(Token::ASSIGN), token_tag(Token::ASSIGNE))))(i)?;
     match op {
         Some(op) => match Node::<Target>::try_from(lhs) {
             Ok(lhs) => {
                 let (i, rhs) = expr(i)?;
-                Ok((i, Node::new(lhs.span + rhs.span, Expr::Assign(lhs, rhs))))
+                let span = lhs.span + rhs.span;
+                Ok((
+                    i,
+                    Node::new(
+                        span,
+                        match *op.data {
+                            Token::ASSIGNE => Expr::Assign(lhs, rhs),
+                            _ => Expr::Stmt(Stmt::Assign { tar: lhs, val: rhs }),
+                        },
+                    ),
+                ))
             }
             Err(_) => Err(Err::Failure(ONode::new(
                 op.span,
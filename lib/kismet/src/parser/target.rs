@@ -2,15 +2,15 @@ use nom::{
     branch::alt,
     combinator::{map, opt},
     multi::separated_list1,
-    sequence::preceded,
+    sequence::{preceded, tuple},
 };
 
 use crate::{
-    ast::{Match, Target, TargetDictItem, TargetExpr, TargetKind, TargetListItem},
+    ast::{Atom, Match, Target, TargetDictItem, TargetExpr, TargetKind, TargetListItem},
     types::Node,
 };
 
-use super::{expr, literal, token_tag, token_tag_id, Input, KResult, Token};
+use super::{expr, literal, numeric_literal, token_tag, token_tag_id, Input, KResult, Token};
 
 pub fn target(i: Input) -> KResult<Node<Target>> {
     map(target_kind(&target), |x| Node::convert(Target, x))(i)
@@ -34,6 +34,17 @@ pub fn target_match(i: Input) -> KResult<Node<Match>> {
             Node::convert(Match::Target, x)
         }),
         map(literal, |x| Node::convert(Match::Literal, x)),
+        map(
+            tuple((token_tag(Token::SUB), numeric_literal)),
+            |(op, x)| {
+                let val = match *x.data {
+                    Atom::Integer(x) => Atom::Integer(-x),
+                    Atom::Float(x) => Atom::Float(-x),
+                    x => x,
+                };
+                Node::new(op.span + x.span, Match::Literal(val))
+            },
+        ),
     ))(i)
 }
 
@@ -129,15 +140,12 @@ where
 {
     move |i| {
         let (i, op) = opt(token_tag(Token::SPREAD))(i)?;
-        match op {
-            Some(op) => {
-                let (i, val) = target_atom(i)?;
-                return Ok((
-                    i,
-                    Node::new(op.span + val.span, TargetDictItem::Spread(val)),
-                ));
-            }
-            None => (),
+        if let Some(op) = op {
+            let (i, val) = target_atom(i)?;
+            return Ok((
+                i,
+                Node::new(op.span + val.span, TargetDictItem::Spread(val)),
+            ));
         }
         let (i, key) = token_tag_id(i)?;
         let (i, val) = opt(preceded(token_tag(Token::COLON), target_atom))(i)?;
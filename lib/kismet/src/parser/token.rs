@@ -33,8 +33,8 @@ impl Iterator for TokenIterator<'_> {
     }
 }
 
-pub fn token<'input>(i: Input<'input>) -> KResult<'input, &Node<Token>> {
-    match i.get(0) {
+pub fn token<'input>(i: Input<'input>) -> KResult<'input, &'input Node<Token>> {
+    match i.first() {
         Some(x) => match *x.data {
             Token::ERROR => Err(Err::Error(ONode::new(x.span, Error::Error(ErrorKind::Lex)))),
             _ => Ok((&i[1..], x)),
@@ -221,6 +221,9 @@ pub enum Token {
     #[regex(r"(?i)d")]
     DIE,
 
+    #[token("!")]
+    BANG,
+
     #[token(".")]
     DOT,
 
@@ -253,10 +256,7 @@ pub enum Token {
     #[regex(r"0x[[:xdigit:]_]*", Token::parse_int)]
     Number(NumberKind),
 
-    #[regex(
-        r"([[:alpha:]--[dD]]|[dD][_[:alpha:]]|_[[:word:]])[[:word:]]*",
-        Token::parse_id
-    )]
+    #[regex(r"([[:alpha:]--[dD]]|[dD][_[:alpha:]]|_)[[:word:]]*", Token::parse_id)]
     Id(String),
 
     #[regex(r"[ \t\f]+", logos::skip)]
@@ -266,6 +266,72 @@ pub enum Token {
     ERROR,
 }
 
+#[derive(Logos, Clone, Debug, PartialEq)]
+pub enum DieToken {
+    #[regex(r"(?i)df")]
+    FUDGE,
+
+    #[regex(r"(?i)kh?")]
+    KH,
+
+    #[regex(r"(?i)kl")]
+    KL,
+
+    #[regex(r"(?i)dh")]
+    DH,
+
+    #[regex(r"(?i)dl")]
+    DL,
+
+    #[regex(r"(?i)r")]
+    R,
+
+    #[regex(r"(?i)ro")]
+    RO,
+
+    #[regex(r"(?i)s")]
+    S,
+
+    #[regex(r"(?i)f")]
+    F,
+
+    #[regex(r"(?i)cs")]
+    CS,
+
+    #[regex(r"(?i)cf")]
+    CF,
+
+    #[token("!")]
+    BANG,
+
+    #[token("!=")]
+    NE,
+
+    #[regex(r"(?i)p")]
+    P,
+
+    #[token("==")]
+    EQ,
+
+    #[token("<")]
+    LT,
+
+    #[token("<=")]
+    LE,
+
+    #[token(">")]
+    GT,
+
+    #[token(">=")]
+    GE,
+
+    #[regex(r"[[:digit:]]+", |t| t.slice().parse::<Integer>().ok())]
+    Number(Integer),
+
+    #[error]
+    ERROR,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum NumberKind {
     Float(Float),
@@ -329,13 +395,10 @@ impl Token {
         match (trunc, frac) {
             (Some(""), Some(frac)) => match parse_str::<LitInt>(frac) {
                 Ok(n) => match n.base10_parse::<Integer>() {
-                    Ok(i) => {
-                        let mag = (i as Float).log10().ceil() as usize;
-                        match mag == frac.len() {
-                            true => Ok(NumberKind::Index(i as usize)),
-                            false => Self::parse_float(t),
-                        }
-                    }
+                    Ok(i) => match frac == i.to_string() {
+                        true => Ok(NumberKind::Index(i as usize)),
+                        false => Self::parse_float(t),
+                    },
                     Err(_) => Err(()),
                 },
                 Err(_) => Self::parse_float(t),
@@ -425,9 +488,8 @@ impl Token {
                 }
                 Part::Hash => {
                     t.bump(1);
-                    match signal {
-                        Some(s) => signal = Some(s + 1),
-                        None => (),
+                    if let Some(s) = signal {
+                        signal = Some(s + 1)
                     }
                 }
                 Part::Chars(s) => {
@@ -436,8 +498,8 @@ impl Token {
                 }
                 Part::Error => return Err(()),
             }
-            match signal {
-                Some(signal_val) => match (signal_val == guard, token) {
+            if let Some(signal_val) = signal {
+                match (signal_val == guard, token) {
                     (true, Part::Quote) | (true, Part::Hash) => {
                         return match parse_str::<LitStr>(t.slice()) {
                             Ok(n) => Ok(n.value()),
@@ -445,8 +507,7 @@ impl Token {
                         };
                     }
                     _ => (),
-                },
-                None => (),
+                }
             }
         }
         Err(())
@@ -491,6 +552,7 @@ impl fmt::Display for Token {
             Self::MOD => write!(f, "%"),
             Self::POW => write!(f, "^"),
             Self::DIE => write!(f, "d"),
+            Self::BANG => write!(f, "!"),
             Self::DOT => write!(f, "."),
             Self::LPAREN => write!(f, "("),
             Self::RPAREN => write!(f, ")"),
@@ -506,6 +568,31 @@ impl fmt::Display for Token {
     }
 }
 
+impl DieToken {
+    pub fn lex(val: &Node<Token>) -> Option<Vec<Node<DieToken>>> {
+        match &*val.data {
+            Token::Id(x) => DieToken::lexer(x)
+                .spanned()
+                .map(|(token, range)| match token {
+                    DieToken::ERROR => None,
+                    token => Some(Node::new(val.span.slice(range), token)),
+                })
+                .collect(),
+            Token::Number(NumberKind::Integer(x)) => {
+                Some(vec![Node::new(val.span, Self::Number(*x))])
+            }
+            Token::BANG => Some(vec![Node::new(val.span, Self::BANG)]),
+            Token::NE => Some(vec![Node::new(val.span, Self::NE)]),
+            Token::EQ => Some(vec![Node::new(val.span, Self::EQ)]),
+            Token::LT => Some(vec![Node::new(val.span, Self::LT)]),
+            Token::LE => Some(vec![Node::new(val.span, Self::LE)]),
+            Token::GT => Some(vec![Node::new(val.span, Self::GT)]),
+            Token::GE => Some(vec![Node::new(val.span, Self::GE)]),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for NumberKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -98,7 +98,7 @@ impl<S, T> Deref for BaseNode<S, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &*self.data
+        &self.data
     }
 }
 
@@ -111,7 +111,7 @@ impl Span {
     where
         Span: From<&'input T>,
     {
-        input.get(0).map(Span::from)
+        input.first().map(Span::from)
     }
 }
 
@@ -1,7 +1,14 @@
-use kismet::ast::OpArith;
+use kismet::{
+    ast::OpArith,
+    hir::{Error, Primitive, Value},
+};
 
 mod util;
-use util::{assert_stmt, new_arith, new_integer, new_op, new_unary};
+use util::{assert_stmt, error, exec, new_arith, new_integer, new_op, new_unary};
+
+fn primitive(input: &str) -> Primitive {
+    Primitive::try_from(exec(input)).unwrap()
+}
 
 #[test]
 fn arithmetic() {
@@ -110,3 +117,74 @@ fn arithmetic() {
         r###"2*-3"###,
     );
 }
+
+#[test]
+fn runtime() {
+    assert_eq!(primitive(r###"x = 3; x + 1"###), Primitive::Integer(4));
+    assert_eq!(
+        primitive(r###"x = 3; y = x * x; y - x"###),
+        Primitive::Integer(6)
+    );
+    assert_eq!(
+        primitive(r###"x = 2; 1 + x ^ 3 * 2"###),
+        Primitive::Integer(17)
+    );
+    assert_eq!(primitive(r###"x = 7; x / 2"###), Primitive::Float(3.5));
+    assert_eq!(primitive(r###"x = 7; x /% 2"###), Primitive::Integer(3));
+    assert_eq!(primitive(r###"x = -7; x /% 2"###), Primitive::Integer(-3));
+    assert_eq!(primitive(r###"x = 7; x /% -2"###), Primitive::Integer(-3));
+    assert_eq!(primitive(r###"-7 /% 2"###), Primitive::Integer(-3));
+    // Float `/%` keeps the Euclidean remainder rather than a floor quotient.
+    assert_eq!(primitive(r###"x = 7.5; x /% 2"###), Primitive::Float(1.5));
+    assert_eq!(
+        error(r###"x = -2147483647 - 1; x /% -1"###),
+        Error::Overflow
+    );
+    assert_eq!(primitive(r###"x = 7; x % 4"###), Primitive::Integer(3));
+    assert_eq!(
+        primitive(r###"x = 2147483647; x + 1"###),
+        Primitive::Float(2147483648.)
+    );
+    assert_eq!(
+        primitive(r###"x = 2; x ^ 40"###),
+        Primitive::Float(1099511627776.)
+    );
+    assert_eq!(primitive(r###"x = 2; x ^ -1"###), Primitive::Float(0.5));
+    assert_eq!(
+        primitive(r###"x = "ab"; x + "c""###),
+        Primitive::String(String::from("abc"))
+    );
+    assert_eq!(primitive(r###"x = 3; 2d1 + x"###), Primitive::Integer(5));
+    assert_eq!(
+        primitive(r###"x = 2; x * 3d1 - 1"###),
+        Primitive::Integer(5)
+    );
+    assert_eq!(
+        primitive(r###"{x := 3; x * 2} + 1"###),
+        Primitive::Integer(7)
+    );
+    assert!(matches!(exec(r###"x = 3; x + 1"###), Value::Primitive(_)));
+
+    assert_eq!(error(r###"x = "a"; x - 1"###), Error::TypeMismatch);
+    assert_eq!(error(r###"x = "a"; x * "b""###), Error::InvalidOp);
+    assert_eq!(error(r###"x = [1]; x + 1"###), Error::TypeMismatch);
+}
+
+#[test]
+fn unary() {
+    assert_eq!(primitive(r###"x = 3; -x"###), Primitive::Integer(-3));
+    assert_eq!(primitive(r###"x = 3; +x"###), Primitive::Integer(3));
+    assert_eq!(primitive(r###"x = 3; -(-x)"###), Primitive::Integer(3));
+    assert_eq!(primitive(r###"x = 1.5; 2 - -x"###), Primitive::Float(3.5));
+    assert_eq!(
+        primitive(r###"x = -2147483647 - 1; -x"###),
+        Primitive::Float(2147483648.)
+    );
+    assert_eq!(primitive(r###"-(3d1)"###), Primitive::Integer(-3));
+    assert_eq!(primitive(r###"-{x := 3; x * 2}"###), Primitive::Integer(-6));
+    assert_eq!(primitive(r###"-(x := 4) + x"###), Primitive::Integer(0));
+
+    assert_eq!(error(r###"x = "a"; -x"###), Error::TypeMismatch);
+    assert_eq!(error(r###"-y"###), Error::TypeMismatch);
+    assert_eq!(error(r###"x = [1]; -x"###), Error::TypeMismatch);
+}
@@ -0,0 +1,47 @@
+use kismet::{
+    analyze, compile,
+    hir::{Collection, Error, Primitive, Value},
+    parse,
+};
+
+mod util;
+use util::{error, exec, integer, list};
+
+#[test]
+fn destructure() {
+    assert_eq!(exec(r###"(a, b) = (1, 2); a * 10 + b"###), integer(12));
+    assert_eq!(exec(r###"[a, b] = (1, 2); b"###), integer(2));
+    assert_eq!(exec(r###"(a, ...rest) = [1, 2, 3]; rest"###), list(&[2, 3]));
+    assert_eq!(exec(r###"(...init, z) = [1, 2, 3]; init"###), list(&[1, 2]));
+    assert_eq!(exec(r###"[a, {b}] = (1, {b: 2}); a + b"###), integer(3));
+    assert_eq!(exec(r###"{a: (x, y)} = {a: [1, 2]}; y"###), integer(2));
+    assert_eq!(
+        exec(r###"stats = {str: 12, dex: 14, con: 8}; {str, dex} = stats; str + dex"###),
+        integer(26)
+    );
+    assert_eq!(
+        exec(r###"{str, ...rest} = {str: 12, dex: 14}; rest"###),
+        exec(r###"{dex: 14}"###)
+    );
+    assert_eq!(
+        exec(r###"((a, b) := (3, 4)) == (3, 4)"###),
+        Value::Primitive(Primitive::Boolean(true))
+    );
+    assert_eq!(
+        exec(r###"a = 1; b = 2; (a, b) = (b, a); (a, b)"###),
+        exec(r###"(2, 1)"###)
+    );
+
+    let Value::Collection(Collection::List(x)) =
+        exec(r###"(a, b, ...rest) = [4d6kh3 for _ in 0..6]; rest"###)
+    else {
+        panic!()
+    };
+    assert_eq!(x.len(), 4);
+
+    assert_eq!(error(r###"(a, b) = [1]"###), Error::TypeMismatch);
+    assert_eq!(error(r###"(a, b) = [1, 2, 3]"###), Error::TypeMismatch);
+    assert_eq!(error(r###"(a, b) = 1"###), Error::TypeMismatch);
+    assert_eq!(error(r###"{str, dex} = {str: 1}"###), Error::TypeMismatch);
+    assert!(analyze(&compile(parse(r###"(a, b) = (1, 2); a"###).unwrap()).unwrap()).is_err());
+}
@@ -0,0 +1,145 @@
+use kismet::{
+    analyze, compile,
+    hir::{Collection, Context, Exec, Pattern, Primitive, Value},
+    parse,
+};
+
+mod util;
+use util::{exec, integer, list, string};
+
+#[test]
+fn if_else() {
+    assert_eq!(
+        exec(r###"x = 3; if x > 1 { "hi" } else { "lo" }"###),
+        string("hi")
+    );
+    assert_eq!(
+        exec(r###"x = 0; if x > 1 { "hi" } else { "lo" }"###),
+        string("lo")
+    );
+    assert_eq!(exec(r###"x = 0; if x { 1 }"###), Value::default());
+    assert_eq!(exec(r###"if 1 > 2 { 1 } else { 2 }"###), integer(2));
+    assert_eq!(exec(r###"if [] { 1 } else { 2 }"###), integer(2));
+    assert_eq!(
+        exec(r###"x = 5; if x < 3 { "low" } else { if x < 6 { "mid" } else { "high" } }"###),
+        string("mid")
+    );
+    assert_eq!(exec(r###"x = 1; y = 0; if x { y = 4 }; y"###), integer(4));
+    assert_eq!(exec(r###"x = 1; if x { y = 4 }; y"###), Value::default());
+    assert_eq!(exec(r###"x = 0; if x { y = 4 }; y"###), Value::default());
+    assert_eq!(exec(r###"if 3d1 == 3 { 1 } else { 0 }"###), integer(1));
+}
+
+#[test]
+fn match_literal() {
+    let table =
+        r###"match x { 1 => "goblin", 2 => "orc", -1 => "ghost", "a" => "imp", _ => "none" }"###;
+    let run = |x: &str| exec(&format!("x = {}; {}", x, table));
+    assert_eq!(run("1"), string("goblin"));
+    assert_eq!(run("2.0"), string("orc"));
+    assert_eq!(run("-1"), string("ghost"));
+    assert_eq!(run(r###""a""###), string("imp"));
+    assert_eq!(run("7"), string("none"));
+    assert_eq!(exec(r###"x = 7; match x { 1 => 1 }"###), Value::default());
+    assert_eq!(
+        exec(r###"match 3d1 { 3 => "three", n => n }"###),
+        string("three")
+    );
+    assert_eq!(
+        exec(r###"x = 4; match x { 1 => 1, n => n * 10 }"###),
+        integer(40)
+    );
+    assert_eq!(
+        exec(r###"x = 4; match x { n => { y := n + 1; y * 2 } }"###),
+        integer(10)
+    );
+}
+
+#[test]
+fn match_destructure() {
+    assert_eq!(
+        exec(r###"match [1, 2, 3] { [a] => a, [a, ...r] => r }"###),
+        list(&[2, 3])
+    );
+    assert_eq!(
+        exec(r###"match [1, 2, 3] { [...r, a] => a }"###),
+        integer(3)
+    );
+    assert_eq!(
+        exec(r###"match [1, 2, 3] { [a, ...r, b] => r }"###),
+        list(&[2])
+    );
+    assert_eq!(exec(r###"match [1] { [a, ...r] => r }"###), list(&[]));
+    assert_eq!(
+        exec(r###"match [1, 2] { [a, b, c] => 0, [a, b] => a + b }"###),
+        integer(3)
+    );
+    assert_eq!(
+        exec(r###"match (1, 2) { [a, b] => 0, (a, b) => a + b }"###),
+        integer(3)
+    );
+    assert_eq!(
+        exec(r###"match (1, 2) { (2, b) => 0, (1, b) => b }"###),
+        integer(2)
+    );
+    assert_eq!(
+        exec(r###"match {a: 1, b: 2} { {c} => 0, {a, ...r} => r }"###),
+        exec(r###"{b: 2}"###)
+    );
+    assert_eq!(
+        exec(r###"match {a: 1, b: 2} { {a: 2} => 0, {a: 1, b} => b }"###),
+        integer(2)
+    );
+    assert_eq!(
+        exec(r###"match {a: [1, 2]} { {a: [_, y]} => y }"###),
+        integer(2)
+    );
+    assert_eq!(
+        exec(r###"match 1 { [a] => a, (a,) => a }"###),
+        Value::default()
+    );
+}
+
+#[test]
+fn pattern() {
+    let x = Pattern::List(vec![]);
+    assert_eq!(x.matches(list(&[])), Some(vec![]));
+    assert_eq!(
+        x.matches(Value::Collection(Collection::Tuple(vec![]))),
+        None
+    );
+    assert!(x
+        .destructure(Value::Collection(Collection::Tuple(vec![])))
+        .is_ok());
+    assert!(x.destructure(list(&[1])).is_err());
+    assert!(compile(parse(r###"match x { [...a, ...b] => 1 }"###).unwrap()).is_err());
+}
+
+#[test]
+fn distribution() {
+    let x =
+        analyze(&compile(parse(r###"if d6 > 4 { 10 } else { 0 }"###).unwrap()).unwrap()).unwrap();
+    assert_eq!(
+        x.iter()
+            .map(|x| (x.0.clone(), (x.1 * 3.).round()))
+            .collect::<Vec<_>>(),
+        vec![(Primitive::Integer(0), 2.), (Primitive::Integer(10), 1.)]
+    );
+    let x =
+        analyze(&compile(parse(r###"match d4 { 1 => 100, n => n }"###).unwrap()).unwrap()).unwrap();
+    assert_eq!(
+        x.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
+        vec![
+            Primitive::Integer(2),
+            Primitive::Integer(3),
+            Primitive::Integer(4),
+            Primitive::Integer(100)
+        ]
+    );
+
+    let x = compile(parse(r###"match d6 { 6 => "crit", _ => "miss" }"###).unwrap())
+        .unwrap()
+        .exec(&mut Context::seeded(1))
+        .unwrap();
+    assert!(x == string("crit") || x == string("miss"));
+}
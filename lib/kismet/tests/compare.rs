@@ -0,0 +1,80 @@
+use kismet::{
+    analyze, ast, compile,
+    hir::{Context, Error, Exec, Primitive, Value},
+    parse,
+};
+
+mod util;
+use util::{boolean, exec};
+
+#[test]
+fn compare() {
+    assert_eq!(exec(r###"1 == 1"###), boolean(true));
+    assert_eq!(exec(r###"1 != 1"###), boolean(false));
+    assert_eq!(exec(r###"1 < 2"###), boolean(true));
+    assert_eq!(exec(r###"2 <= 2"###), boolean(true));
+    assert_eq!(exec(r###"1 > 2"###), boolean(false));
+    assert_eq!(exec(r###"1 >= 2"###), boolean(false));
+    assert_eq!(exec(r###"1 == 1.0"###), boolean(true));
+    assert_eq!(exec(r###"1 < 1.5"###), boolean(true));
+    assert_eq!(exec(r###"2.5 >= 2"###), boolean(true));
+    assert_eq!(exec(r###""a" < "b""###), boolean(true));
+    assert_eq!(exec(r###""ab" == "ab""###), boolean(true));
+    assert_eq!(exec(r###""a" == 1"###), boolean(false));
+    assert_eq!(exec(r###""a" != 1"###), boolean(true));
+    assert_eq!(exec(r###"x = 3; x > 2"###), boolean(true));
+    assert_eq!(exec(r###"x = 3; 2 * x == 6"###), boolean(true));
+    assert_eq!(exec(r###"3d1 == 3"###), boolean(true));
+
+    let x = compile(parse(r###""a" < 1"###).unwrap());
+    assert_eq!(
+        x.map_err(|x| *x.data),
+        Err(Error::Ast(ast::Error::TypeMismatch))
+    );
+    let x = compile(parse(r###"x = "a"; x < 1"###).unwrap())
+        .unwrap()
+        .exec(&mut Context::default());
+    assert_eq!(x.map_err(|x| *x.data), Err(Error::TypeMismatch));
+}
+
+#[test]
+fn structural() {
+    assert_eq!(exec(r###"[1, 2] == [1, 2]"###), boolean(true));
+    assert_eq!(exec(r###"[1, 2] == [1, 2.0]"###), boolean(true));
+    assert_eq!(exec(r###"[1, 2] == [2, 1]"###), boolean(false));
+    assert_eq!(exec(r###"[1, 2] != [1, 2, 3]"###), boolean(true));
+    assert_eq!(exec(r###"(1, "a") == (1, "a")"###), boolean(true));
+    assert_eq!(exec(r###"(1, 2) == [1, 2]"###), boolean(false));
+    assert_eq!(exec(r###"[[1], (2,)] == [[1], (2,)]"###), boolean(true));
+    assert_eq!(exec(r###"{a: 1, b: 2} == {b: 2, a: 1}"###), boolean(true));
+    assert_eq!(exec(r###"{a: 1, b: 2} == {a: 1, b: 3}"###), boolean(false));
+    assert_eq!(exec(r###"{a: 1} == {a: 1, b: 2}"###), boolean(false));
+    assert_eq!(exec(r###"x = [1]; x == [1]"###), boolean(true));
+    assert!(compile(parse(r###"[1] < [2]"###).unwrap()).is_err());
+}
+
+#[test]
+fn bound() {
+    assert_eq!(exec(r###"1 < 2 < 3"###), boolean(true));
+    assert_eq!(exec(r###"1 < 3 < 2"###), boolean(false));
+    assert_eq!(exec(r###"3 > 2 >= 2"###), boolean(true));
+    assert_eq!(exec(r###"x = 5; 1 <= x < 10"###), boolean(true));
+    assert_eq!(exec(r###"x = 10; 1 <= x < 10"###), boolean(false));
+    assert_eq!(exec(r###"x = 0; 1 <= x < 10"###), boolean(false));
+    assert_eq!(exec(r###"2 <= 2d6 <= 12"###), boolean(true));
+    assert_eq!(
+        exec(r###"x = 0; 0 < (x := x + 1) <= 1 and x == 1"###),
+        boolean(true)
+    );
+    assert_eq!(exec(r###"x = 0; 5 < x < (y := 1); y"###), Value::default());
+
+    let x = analyze(&compile(parse(r###"P(3 <= d6 <= 4)"###).unwrap()).unwrap()).unwrap();
+    assert_eq!(x.single(), Some(&Primitive::Float(1. / 3.)));
+    let x = analyze(&compile(parse(r###"P(d4 < d6 < d8)"###).unwrap()).unwrap()).unwrap();
+    let p = (1..=4)
+        .flat_map(|a| (1..=6).flat_map(move |b| (1..=8).map(move |c| (a, b, c))))
+        .filter(|(a, b, c)| a < b && b < c)
+        .count() as f32
+        / 192.;
+    assert_eq!(x.single(), Some(&Primitive::Float(p)));
+}
@@ -0,0 +1,99 @@
+use kismet::{
+    analyze, compile,
+    hir::{Collection, Error, Value},
+    parse,
+};
+
+mod util;
+use util::{error, exec, integer, list};
+
+#[test]
+fn list_comprehension() {
+    assert_eq!(exec(r###"[x * 2 for x in 0..4]"###), list(&[0, 2, 4, 6]));
+    assert_eq!(
+        exec(r###"[x for x in 0..10 if x % 3 == 0]"###),
+        list(&[0, 3, 6, 9])
+    );
+    assert_eq!(
+        exec(r###"[x * 10 + y for x in 1..3 for y in 0..x]"###),
+        list(&[10, 20, 21])
+    );
+    assert_eq!(
+        exec(r###"[a + b for (a, b) in [(1, 2), (3, 4)]]"###),
+        list(&[3, 7])
+    );
+    assert_eq!(
+        exec(r###"[...[x, x] for x in [1, 2]]"###),
+        list(&[1, 1, 2, 2])
+    );
+    assert_eq!(exec(r###"[x for x in []]"###), list(&[]));
+    assert_eq!(exec(r###"x = 5; [x for x in 0..2]; x"###), integer(5));
+
+    let Value::Collection(Collection::List(x)) = exec(r###"[1d6 for _ in 0..6]"###) else {
+        panic!()
+    };
+    assert_eq!(x.len(), 6);
+
+    assert_eq!(error(r###"[x for x in 1]"###), Error::TypeMismatch);
+    assert_eq!(error(r###"[x for x in (0..)]"###), Error::IterationLimit);
+}
+
+#[test]
+fn dict_comprehension() {
+    assert_eq!(
+        exec(r###"{[k]: v * 2 for (k, v) in {a: 1, b: 2}}"###),
+        exec(r###"{a: 2, b: 4}"###)
+    );
+    assert_eq!(
+        exec(r###"{...x for x in [{a: 1}, {b: 2}, {a: 3}]}"###),
+        exec(r###"{a: 3, b: 2}"###)
+    );
+    assert_eq!(exec(r###"x = {["a"]: 1}; x.a"###), integer(1));
+
+    let Value::Collection(Collection::Dict(x)) =
+        exec(r###"party = ["alice", "bob"]; {[name]: 1d20 for name in party}"###)
+    else {
+        panic!()
+    };
+    assert_eq!(x.len(), 2);
+
+    assert_eq!(error(r###"{[x]: x for x in 0..2}"###), Error::TypeMismatch);
+    assert_eq!(error(r###"{...x for x in 0..2}"###), Error::TypeMismatch);
+}
+
+#[test]
+fn generator() {
+    assert_eq!(
+        exec(r###"g = (x * x for x in 0..4); [...g]"###),
+        list(&[0, 1, 4, 9])
+    );
+    assert_eq!(
+        exec(r###"g = (x for x in (0..) if x % 7 == 3); for x in g { if x > 20 { break x } }"###),
+        integer(24)
+    );
+    assert_eq!(
+        exec(
+            r###"g = (x for x in 0..3); n = 0; for x in g { n = n + x }; for x in g { n = n + x }; n"###
+        ),
+        integer(6)
+    );
+    assert_eq!(
+        exec(r###"[y * 2 for y in (x + 1 for x in 0..3)]"###),
+        list(&[2, 4, 6])
+    );
+    assert_eq!(
+        exec(r###"n = 0; g = (n for _ in 0..2); n = 1; [...g]"###),
+        list(&[0, 0])
+    );
+    assert_eq!(exec(r###"(x for x in 0..3)"###).to_string(), "generator");
+
+    assert_eq!(
+        error(r###"g = (x for x in (0..)); [...g]"###),
+        Error::IterationLimit
+    );
+    assert_eq!(
+        error(r###"g = (x for x in 1); [...g]"###),
+        Error::TypeMismatch
+    );
+    assert!(analyze(&compile(parse(r###"[d6 for _ in 0..2]"###).unwrap()).unwrap()).is_err());
+}
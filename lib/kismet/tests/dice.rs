@@ -0,0 +1,450 @@
+use kismet::{
+    ast::{
+        Atom, DieCompare, DieMod, Expr, ListItem, Op, OpCrit, OpEqs, OpExplode, OpKeep, OpReroll,
+        Primary,
+    },
+    compile,
+    hir::{
+        Collection, Context, Dice, Die, Error, Exec, Instruction, Primitive, Roll, Sides, Value,
+    },
+    parse,
+    types::{Node, Span},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+mod util;
+use util::{assert_stmt, exec, new_coefficient, new_die, new_id};
+
+fn total(input: &str) -> Primitive {
+    Primitive::try_from(exec(input)).unwrap()
+}
+
+fn eval(input: &str, seed: u64) -> Roll {
+    let node = compile(parse(input).unwrap()).unwrap();
+    match &*node.data.0[0].data {
+        Instruction::Dice(x) => x.eval(&mut Context::seeded(seed)).unwrap(),
+        x => panic!("Expected dice, got {:?}", x),
+    }
+}
+
+#[test]
+fn dice() {
+    assert_stmt(
+        new_die(0..2, Node::new(1..2, Atom::Integer(6)), vec![]),
+        r###"d6"###,
+    );
+    assert_stmt(
+        new_coefficient(
+            Node::new(0..1, Atom::Integer(3)),
+            new_die(1..3, Node::new(2..3, Atom::Integer(6)), vec![]),
+        ),
+        r###"3d6"###,
+    );
+    for _ in 0..100 {
+        match total(r###"3d6"###) {
+            Primitive::Integer(x) => assert!((3..=18).contains(&x)),
+            x => panic!("Expected integer, got {:?}", x),
+        }
+        match total(r###"(1+2)d(2*3)"###) {
+            Primitive::Integer(x) => assert!((3..=18).contains(&x)),
+            x => panic!("Expected integer, got {:?}", x),
+        }
+    }
+    assert_eq!(total(r###"0d6"###), Primitive::Integer(0));
+    assert_eq!(exec(r###"2(3)"###), Value::Primitive(Primitive::Integer(6)));
+}
+
+#[test]
+fn roll() {
+    let roll = Dice::roll(&mut StdRng::seed_from_u64(42), 1000, 6).unwrap();
+    let faces = roll.dice.iter().map(|x| x.face).collect::<Vec<_>>();
+    assert_eq!(faces.len(), 1000);
+    assert!(faces.iter().all(|x| (1..=6).contains(x)));
+    assert!((1..=6).all(|x| faces.contains(&x)));
+    assert_eq!(
+        roll,
+        Dice::roll(&mut StdRng::seed_from_u64(42), 1000, 6).unwrap()
+    );
+    assert!(Dice::roll(&mut StdRng::seed_from_u64(42), 1, 0).is_err());
+}
+
+#[test]
+fn limit() {
+    let node = compile(parse(r###"1000000000d6"###).unwrap()).unwrap();
+    assert_eq!(
+        node.exec(&mut Context::seeded(0)).map_err(|x| *x.data),
+        Err(Error::DiceLimit)
+    );
+    let node = compile(parse(r###"100d6"###).unwrap()).unwrap();
+    let mut i = Context::seeded(0);
+    assert!(node.exec(&mut i).is_ok());
+    i.max_dice = 99;
+    assert_eq!(
+        node.exec(&mut i).map_err(|x| *x.data),
+        Err(Error::DiceLimit)
+    );
+}
+
+#[test]
+fn seeded() {
+    let node = compile(parse(r###"[10d6, 10d6, 10d6]"###).unwrap()).unwrap();
+    let roll = |seed| node.exec(&mut Context::seeded(seed)).unwrap();
+    assert_eq!(roll(42), roll(42));
+    assert_ne!(roll(42), roll(43));
+    let mut i = Context::with_rng(StdRng::seed_from_u64(42));
+    let mut j = Context::with_rng(StdRng::seed_from_u64(42));
+    assert_eq!(node.exec(&mut i).unwrap(), node.exec(&mut j).unwrap());
+}
+
+#[test]
+fn keep() {
+    assert_stmt(
+        new_coefficient(
+            Node::new(0..1, Atom::Integer(4)),
+            new_die(
+                1..6,
+                Node::new(2..3, Atom::Integer(6)),
+                vec![Node::new(
+                    3..6,
+                    DieMod::Keep(
+                        Node::new(3..5, OpKeep::KH),
+                        Some(Node::new(5..6, Atom::Integer(3))),
+                    ),
+                )],
+            ),
+        ),
+        r###"4d6kh3"###,
+    );
+    for seed in 0..20 {
+        let x = eval(r###"4d6kh3"###, seed);
+        let min = x.kept().map(|x| x.face).min().unwrap();
+        assert_eq!(x.kept().count(), 3);
+        assert!(x.dropped().all(|x| x.face <= min));
+
+        let x = eval(r###"2d20kl1"###, seed);
+        let max = x.kept().map(|x| x.face).max().unwrap();
+        assert_eq!(x.kept().count(), 1);
+        assert!(x.dropped().all(|x| x.face >= max));
+
+        let x = eval(r###"4d6dl1"###, seed);
+        assert_eq!(x.kept().count(), 3);
+        assert_eq!(x.total(), eval(r###"4d6kh3"###, seed).total());
+
+        let x = eval(r###"4d6dh1"###, seed);
+        assert_eq!(x.total(), eval(r###"4d6kl3"###, seed).total());
+    }
+}
+
+#[test]
+fn explode() {
+    assert_stmt(
+        new_die(
+            0..6,
+            Node::new(1..2, Atom::Integer(6)),
+            vec![Node::new(
+                2..6,
+                DieMod::Explode(
+                    Node::new(2..3, OpExplode::EXPLODE),
+                    Some(Node::new(
+                        3..6,
+                        DieCompare {
+                            op: Some(Node::new(3..5, OpEqs::GE)),
+                            val: Node::new(5..6, Atom::Integer(5)),
+                        },
+                    )),
+                ),
+            )],
+        ),
+        r###"d6!>=5"###,
+    );
+    let mut i = Context::seeded(42);
+    i.max_explode = 10;
+    let node = compile(parse(r###"d1!"###).unwrap()).unwrap();
+    assert_eq!(
+        Primitive::try_from(node.exec(&mut i).unwrap()),
+        Ok(Primitive::Integer(11))
+    );
+    assert_eq!(eval(r###"3d1!!"###, 0).dice.len(), 3);
+    for seed in 0..20 {
+        let x = eval(r###"10d6!"###, seed);
+        assert!(x.dice.len() >= 10);
+        assert!(x.dice.iter().all(|x| x.exploded == (x.face == 6)));
+
+        let x = eval(r###"10d6!>4"###, seed);
+        assert!(x.dice.iter().all(|x| x.exploded == (x.face > 4)));
+
+        let x = eval(r###"10d6!=5"###, seed);
+        assert!(x.dice.iter().all(|x| x.exploded == (x.face == 5)));
+
+        let x = eval(r###"10d6!!"###, seed);
+        assert_eq!(x.dice.len(), 10);
+        assert!(x.dice.iter().all(|x| x.exploded == (x.face >= 6)));
+
+        let x = eval(r###"10d6!p"###, seed);
+        let mut prev = false;
+        for x in x.dice.iter() {
+            assert!(if prev {
+                (0..=5).contains(&x.face)
+            } else {
+                (1..=6).contains(&x.face)
+            });
+            prev = x.exploded;
+        }
+
+        let x = eval(r###"4d6!kh3"###, seed);
+        assert_eq!(x.kept().count(), 3);
+    }
+}
+
+#[test]
+fn reroll() {
+    assert_stmt(
+        new_coefficient(
+            Node::new(0..1, Atom::Integer(2)),
+            new_die(
+                1..7,
+                Node::new(2..4, Atom::Integer(20)),
+                vec![Node::new(
+                    4..7,
+                    DieMod::Reroll(
+                        Node::new(4..5, OpReroll::REROLL),
+                        Some(Node::new(
+                            5..7,
+                            DieCompare {
+                                op: Some(Node::new(5..6, OpEqs::LT)),
+                                val: Node::new(6..7, Atom::Integer(3)),
+                            },
+                        )),
+                    ),
+                )],
+            ),
+        ),
+        r###"2d20r<3"###,
+    );
+    let x = eval(r###"d6r<7"###, 0);
+    assert_eq!(x.dice[0].rerolled.len(), 100);
+    for seed in 0..20 {
+        let x = eval(r###"10d6r"###, seed);
+        assert!(x.dice.iter().all(|x| x.face != 1));
+        assert!(x.dice.iter().all(|x| x.rerolled.iter().all(|x| *x == 1)));
+
+        let x = eval(r###"10d6r<3"###, seed);
+        assert!(x.dice.iter().all(|x| x.face >= 3));
+        assert!(x.dice.iter().all(|x| x.rerolled.iter().all(|x| *x < 3)));
+
+        let x = eval(r###"10d6ro"###, seed);
+        assert!(x.dice.iter().all(|x| x.rerolled.len() <= 1));
+        assert!(x.dice.iter().all(|x| x.rerolled.iter().all(|x| *x == 1)));
+
+        let x = eval(r###"4d6ro<2kh3"###, seed);
+        assert_eq!(x.kept().count(), 3);
+    }
+}
+
+#[test]
+fn success() {
+    assert_stmt(
+        new_coefficient(
+            Node::new(0..2, Atom::Integer(10)),
+            new_die(
+                2..11,
+                Node::new(3..5, Atom::Integer(10)),
+                vec![
+                    Node::new(
+                        5..9,
+                        DieMod::Success(Node::new(
+                            6..9,
+                            DieCompare {
+                                op: Some(Node::new(6..8, OpEqs::GE)),
+                                val: Node::new(8..9, Atom::Integer(8)),
+                            },
+                        )),
+                    ),
+                    Node::new(
+                        9..11,
+                        DieMod::Failure(Node::new(
+                            10..11,
+                            DieCompare {
+                                op: None,
+                                val: Node::new(10..11, Atom::Integer(1)),
+                            },
+                        )),
+                    ),
+                ],
+            ),
+        ),
+        r###"10d10s>=8f1"###,
+    );
+    assert_stmt(
+        new_die(
+            0..7,
+            Node::new(1..3, Atom::Integer(20)),
+            vec![
+                Node::new(3..5, DieMod::Crit(Node::new(3..5, OpCrit::SUCCESS), None)),
+                Node::new(5..7, DieMod::Crit(Node::new(5..7, OpCrit::FAILURE), None)),
+            ],
+        ),
+        r###"d20cscf"###,
+    );
+    assert_eq!(
+        parse(r###"d20>=11"###).unwrap().to_string(),
+        parse(r###"d20 >= 11"###).unwrap().to_string()
+    );
+    assert_eq!(
+        parse(r###"d6!=6"###).unwrap().to_string(),
+        parse(r###"d6!6"###).unwrap().to_string()
+    );
+    assert!(matches!(
+        exec(r###"d20==20"###),
+        Value::Primitive(Primitive::Boolean(_))
+    ));
+    assert_eq!(total(r###"5d1s>=1"###), Primitive::Integer(5));
+    assert_eq!(total(r###"5d1s>=1f1"###), Primitive::Integer(0));
+    for seed in 0..20 {
+        let x = eval(r###"10d10s>=8f1"###, seed);
+        assert!(x.dice.iter().all(|x| x.success == (x.face >= 8)));
+        assert!(x.dice.iter().all(|x| x.failure == (x.face == 1)));
+        let count = x.dice.iter().filter(|x| x.success).count() as i32
+            - x.dice.iter().filter(|x| x.failure).count() as i32;
+        assert_eq!(x.total(), Primitive::Integer(count));
+
+        let x = eval(r###"10d20cs>=19cf"###, seed);
+        assert!(!x.count);
+        assert!(x.dice.iter().all(|x| match x.face {
+            19 | 20 => x.crit == Some(OpCrit::SUCCESS),
+            1 => x.crit == Some(OpCrit::FAILURE),
+            _ => x.crit.is_none(),
+        }));
+
+        let x = eval(r###"4d6kh3s>4"###, seed);
+        let count = x.kept().filter(|x| x.face > 4).count() as i32;
+        assert_eq!(x.total(), Primitive::Integer(count));
+    }
+}
+
+#[test]
+fn faces() {
+    assert_stmt(
+        new_coefficient(
+            Node::new(0..1, Atom::Integer(4)),
+            Node::new(1..3, Expr::Op(Op::Fudge(Span::new(1..3), vec![]))),
+        ),
+        r###"4dF"###,
+    );
+    assert_stmt(
+        new_die(
+            0..6,
+            Node::new(
+                1..6,
+                Atom::ListDisplay(vec![
+                    Node::new(
+                        2..3,
+                        ListItem::Expr(Expr::Primary(Primary::Atom(Atom::Integer(1)))),
+                    ),
+                    Node::new(
+                        4..5,
+                        ListItem::Expr(Expr::Primary(Primary::Atom(Atom::Integer(2)))),
+                    ),
+                ]),
+            ),
+            vec![],
+        ),
+        r###"d[1,2]"###,
+    );
+    assert_stmt(new_id(0..4, "dFoo"), r###"dFoo"###);
+    assert_stmt(new_id(0..2, "df"), r###"df"###);
+    assert_stmt(new_id(0..4, "dFkh"), r###"dFkh"###);
+    assert_eq!(
+        exec(r###"df = 3; df"###),
+        Value::Primitive(Primitive::Integer(3))
+    );
+    assert_eq!(
+        exec(r###"df = 3; dfr = 4; df + dfr"###),
+        Value::Primitive(Primitive::Integer(7))
+    );
+    for seed in 0..20 {
+        let x = eval(r###"4dF"###, seed);
+        assert_eq!(x.sides, Sides::Faces(vec![-1, 0, 1]));
+        assert!(x.dice.iter().all(|x| (-1..=1).contains(&x.face)));
+
+        let x = eval(r###"dF"###, seed);
+        assert_eq!(x.dice.len(), 1);
+        let x = eval(r###"2dFkh"###, seed);
+        assert_eq!(x.kept().count(), 1);
+
+        let x = eval(r###"10d[1,1,2,3,5,8]"###, seed);
+        assert!(x.dice.iter().all(|x| [1, 2, 3, 5, 8].contains(&x.face)));
+        let x = eval(r###"10d[1,1,2,3,5,8]!"###, seed);
+        assert!(x.dice.iter().all(|x| x.exploded == (x.face == 8)));
+    }
+    for _ in 0..20 {
+        match exec(r###"3d["hit","miss","crit"]"###) {
+            Value::Collection(Collection::List(x)) => {
+                assert_eq!(x.len(), 3);
+                assert!(x.iter().all(|x| match x {
+                    Value::Primitive(Primitive::String(x)) =>
+                        ["hit", "miss", "crit"].contains(&x.as_str()),
+                    _ => false,
+                }));
+            }
+            x => panic!("Expected list, got {:?}", x),
+        }
+    }
+    assert_eq!(total(r###"d[7]"###), Primitive::Integer(7));
+    let node = compile(parse(r###"d["a"]kh"###).unwrap()).unwrap();
+    assert!(node.exec(&mut Context::default()).is_err());
+}
+
+#[test]
+fn display() {
+    let mut roll = Roll {
+        notation: String::from("4d6kh3"),
+        dice: [6, 5, 3, 1].into_iter().map(Die::new).collect(),
+        sides: Sides::Range(6),
+        count: false,
+    };
+    roll.keep(OpKeep::KH, 3).unwrap();
+    assert_eq!(roll.to_string(), "4d6kh3 = [6, 5, 3, ~~1~~] = 14");
+    roll.dice[0].exploded = true;
+    roll.dice[1].rerolled = vec![1];
+    assert_eq!(roll.to_string(), "4d6kh3 = [6!, (1), 5, 3, ~~1~~] = 14");
+    assert_eq!(exec(r###"2d1"###).to_string(), "2d1 = [1, 1] = 2");
+    assert_eq!(exec(r###"2d1s1"###).to_string(), "2d1s1 = [1+, 1+] = 2");
+    assert_eq!(exec(r###"2d1f1"###).to_string(), "2d1f1 = [1-, 1-] = -2");
+    assert_eq!(exec(r###"d1cs"###).to_string(), "d1cs = [1^] = 1");
+    assert_eq!(exec(r###"d1cf"###).to_string(), "d1cf = [1_] = 1");
+    assert_eq!(exec(r###"d1s1cs"###).to_string(), "d1s1cs = [1+^] = 1");
+    assert_eq!(
+        exec(r###"(1+1)dF"###).to_string().split(" = ").next(),
+        Some("(1 + 1)dF")
+    );
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(
+        exec(r###"d1 + 2"###),
+        Value::Primitive(Primitive::Integer(3))
+    );
+    assert_eq!(
+        exec(r###"10d1 * 2"###),
+        Value::Primitive(Primitive::Integer(20))
+    );
+    assert_eq!(
+        exec(r###"3d1 / 2"###),
+        Value::Primitive(Primitive::Float(1.5))
+    );
+    assert_eq!(
+        exec(r###"2d1 - 3d1"###),
+        Value::Primitive(Primitive::Integer(-1))
+    );
+    assert_eq!(total(r###"(2d1)d1"###), Primitive::Integer(2));
+    for _ in 0..100 {
+        match exec(r###"4d6kh3 + 1"###) {
+            Value::Primitive(Primitive::Integer(x)) => assert!((4..=19).contains(&x)),
+            x => panic!("Expected integer, got {:?}", x),
+        }
+    }
+    let node = compile(parse(r###"d["a"] + 1"###).unwrap()).unwrap();
+    assert!(node.exec(&mut Context::default()).is_err());
+}
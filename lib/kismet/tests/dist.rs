@@ -0,0 +1,284 @@
+use kismet::{
+    analyze,
+    ast::{Id, OpArith},
+    compile,
+    hir::{Analysis, Bind, Block, Context, Dist, Error, Exec, Instruction, Primitive, Value},
+    parse,
+    types::Node,
+};
+
+fn dist(input: &str) -> Dist {
+    analyze(&compile(parse(input).unwrap()).unwrap()).unwrap()
+}
+
+fn prob(dist: &Dist, val: i32) -> f64 {
+    dist.iter()
+        .find(|(x, _)| *x == Primitive::Integer(val))
+        .map(|(_, p)| *p)
+        .unwrap_or_default()
+}
+
+fn query(input: &str) -> f64 {
+    match compile(parse(input).unwrap())
+        .unwrap()
+        .exec(&mut Context::default())
+        .unwrap()
+    {
+        Value::Primitive(Primitive::Float(x)) => x as f64,
+        Value::Primitive(Primitive::Integer(x)) => x as f64,
+        x => panic!("{}", x),
+    }
+}
+
+fn assert_close(lhs: f64, rhs: f64) {
+    assert!((lhs - rhs).abs() < 1e-9, "{} != {}", lhs, rhs);
+}
+
+fn brute<F>(count: u32, sides: i32, f: F) -> Vec<(i32, f64)>
+where
+    F: Fn(&[i32]) -> i32,
+{
+    let total = (sides as f64).powi(count as i32);
+    let mut acc = std::collections::BTreeMap::<i32, f64>::new();
+    for n in 0..(sides as usize).pow(count) {
+        let faces = (0..count)
+            .map(|x| (n / (sides as usize).pow(x) % sides as usize) as i32 + 1)
+            .collect::<Vec<_>>();
+        *acc.entry(f(&faces)).or_default() += 1. / total;
+    }
+    acc.into_iter().collect()
+}
+
+fn assert_brute(dist: &Dist, brute: Vec<(i32, f64)>) {
+    assert_eq!(dist.len(), brute.len());
+    for (val, p) in brute {
+        assert_close(prob(dist, val), p);
+    }
+}
+
+#[test]
+fn sum() {
+    let x = dist(r###"2d6"###);
+    assert_eq!(x.len(), 11);
+    assert_close(prob(&x, 7), 6. / 36.);
+    assert_close(x.mean().unwrap(), 7.);
+    assert_close(x.variance().unwrap(), 35. / 6.);
+    assert_close(x.iter().map(|x| x.1).sum(), 1.);
+
+    let x = dist(r###"2d6+3"###);
+    assert_close(prob(&x, 10), 6. / 36.);
+    assert_close(x.mean().unwrap(), 10.);
+
+    let x = dist(r###"2d6 - d4"###);
+    assert_close(x.mean().unwrap(), 4.5);
+    assert_eq!(dist(r###"3"###), Dist::constant(Primitive::Integer(3)));
+    assert_close(dist(r###"d6 / 2"###).mean().unwrap(), 1.75);
+    assert_close(dist(r###"(d2)d6"###).mean().unwrap(), 5.25);
+    assert_close(dist(r###"-d6 + 7"###).mean().unwrap(), 3.5);
+    assert_close(prob(&dist(r###"-(2d6)"###), -12), 1. / 36.);
+}
+
+#[test]
+fn keep() {
+    let x = dist(r###"4d6kh3"###);
+    assert_brute(
+        &x,
+        brute(4, 6, |x| x.iter().sum::<i32>() - x.iter().min().unwrap()),
+    );
+    assert_close(x.mean().unwrap(), 15869. / 1296.);
+    assert_eq!(x, dist(r###"4d6dl1"###));
+    assert_brute(
+        &dist(r###"3d6kl2"###),
+        brute(3, 6, |x| x.iter().sum::<i32>() - x.iter().max().unwrap()),
+    );
+    assert_eq!(dist(r###"3d6kl2"###), dist(r###"3d6dh1"###));
+    assert_close(prob(&dist(r###"2d20kh1"###), 20), 39. / 400.);
+    assert_close(
+        prob(&dist(r###"100d6kh1"###), 6),
+        1. - (5f64 / 6.).powi(100),
+    );
+}
+
+#[test]
+fn reroll() {
+    let x = dist(r###"d6r"###);
+    assert_close(prob(&x, 1), 0.);
+    assert_close(prob(&x, 2), 0.2);
+    assert_close(x.mean().unwrap(), 4.);
+    let x = dist(r###"d6ro"###);
+    assert_close(prob(&x, 1), 1. / 36.);
+    assert_close(prob(&x, 6), 7. / 36.);
+    assert_brute(
+        &dist(r###"2d6ro<3"###),
+        brute(4, 6, |x| {
+            let a = if x[0] < 3 { x[1] } else { x[0] };
+            let b = if x[2] < 3 { x[3] } else { x[2] };
+            a + b
+        }),
+    );
+}
+
+#[test]
+fn explode() {
+    assert_close(dist(r###"d6!"###).mean().unwrap(), 4.2);
+    assert_close(dist(r###"3d6!!"###).mean().unwrap(), 12.6);
+    assert_close(dist(r###"d6!p"###).mean().unwrap(), 3.5 + 0.5);
+    let x = dist(r###"d6!>=5"###);
+    assert_close(prob(&x, 4), 1. / 6.);
+    assert_close(prob(&x, 5), 0.);
+    assert_close(prob(&x, 6), 1. / 36.);
+
+    let mut i = Analysis {
+        max_explode: 3,
+        ..Analysis::default()
+    };
+    let x: Dist = compile(parse(r###"d1!"###).unwrap())
+        .unwrap()
+        .exec(&mut i)
+        .unwrap();
+    assert_eq!(x, Dist::constant(Primitive::Integer(4)));
+    for input in [r###"4d6!kh3"###, r###"4d6!pdl1"###, r###"4d6!>5kl2"###] {
+        assert_eq!(
+            analyze(&compile(parse(input).unwrap()).unwrap()).map_err(|x| *x.data),
+            Err(Error::NotAnalysable)
+        );
+    }
+    assert!(analyze(&compile(parse(r###"4d6!!kh3"###).unwrap()).unwrap()).is_ok());
+}
+
+#[test]
+fn limit() {
+    let limit = |input: &str, i: &Context| {
+        let x: Result<Dist, _> = compile(parse(input).unwrap())
+            .unwrap()
+            .exec(&mut Analysis::from(i));
+        x.map_err(|x| *x.data)
+    };
+    let mut i = Context::default();
+    assert_eq!(limit(r###"100000d6"###, &i), Err(Error::DiceLimit));
+    assert_eq!(limit(r###"P(100000d6 > 5)"###, &i), Err(Error::DiceLimit));
+    assert!(limit(r###"4d6"###, &i).is_ok());
+    i.max_dice = 3;
+    assert_eq!(limit(r###"4d6"###, &i), Err(Error::DiceLimit));
+}
+
+#[test]
+fn success() {
+    let x = dist(r###"10d10s>=8"###);
+    assert_eq!(x.len(), 11);
+    assert_close(x.mean().unwrap(), 3.);
+    assert_close(prob(&x, 10), 0.3f64.powi(10));
+    let x = dist(r###"10d10s>=8f1"###);
+    assert_close(x.mean().unwrap(), 2.);
+    assert_close(prob(&x, -10), 0.1f64.powi(10));
+    assert_brute(
+        &dist(r###"4d6kh3s>4"###),
+        brute(4, 6, |x| {
+            let mut x = x.to_vec();
+            x.sort();
+            x[1..].iter().filter(|x| **x > 4).count() as i32
+        }),
+    );
+}
+
+#[test]
+fn faces() {
+    let x = dist(r###"4dF"###);
+    assert_eq!(x.len(), 9);
+    assert_close(x.mean().unwrap(), 0.);
+    assert_close(prob(&x, 4), 1. / 81.);
+    assert_close(dist(r###"d[1,1,2]"###).mean().unwrap(), 4. / 3.);
+    assert!(analyze(&compile(parse(r###"d["a"]"###).unwrap()).unwrap()).is_err());
+}
+
+#[test]
+fn variables() {
+    let die = compile(parse(r###"d6"###).unwrap()).unwrap();
+    let die = die.data.0[0].clone();
+    let x = Node::new(0..0, Instruction::Variable(Id(String::from("x"))));
+    let block = Node::new(
+        0..0,
+        Block(vec![
+            Node::new(
+                0..0,
+                Instruction::Assign(Bind::Rebind, Id(String::from("x")), die),
+            ),
+            Node::new(0..0, Instruction::Arith(x.clone(), OpArith::ADD, x)),
+        ]),
+    );
+    let x = analyze(&block).unwrap();
+    assert_eq!(x.len(), 6);
+    assert!((1..=6).all(|n| (prob(&x, 2 * n) - 1. / 6.).abs() < 1e-9));
+}
+
+#[test]
+fn compare() {
+    let x = dist(r###"2d6 >= 7"###);
+    assert_eq!(x.len(), 2);
+    assert_eq!(
+        x.iter()
+            .find(|x| x.0 == Primitive::Boolean(true))
+            .unwrap()
+            .1 as f32,
+        (21. / 36.) as f32
+    );
+    assert_eq!(
+        dist(r###"3 < 4"###),
+        Dist::constant(Primitive::Boolean(true))
+    );
+    let x = dist(r###"d6 > 6"###);
+    assert_eq!(x.single(), Some(&Primitive::Boolean(false)));
+    assert!(analyze(&compile(parse(r###"d6 < "a""###).unwrap()).unwrap()).is_err());
+}
+
+#[test]
+fn queries() {
+    assert!((query(r###"P(2d20kh1 + 5 >= 15)"###) - 0.7975).abs() < 1e-6);
+    assert!((query(r###"P(d6 == 6) * 6"###) - 1.).abs() < 1e-6);
+    assert_eq!(query(r###"P(d20>=11)"###), query(r###"P(d20 >= 11)"###));
+    assert!((query(r###"E(2d6 + 3)"###) - 10.).abs() < 1e-6);
+    assert!((query(r###"E(4d6kh3)"###) - 15869. / 1296.).abs() < 1e-5);
+    assert!((query(r###"Var(2d6)"###) - 35. / 6.).abs() < 1e-6);
+    assert_eq!(query(r###"percentile(3d6, 50)"###), 10.);
+    assert_eq!(query(r###"percentile(d20, 95)"###), 19.);
+    assert_eq!(query(r###"percentile(d20, 100)"###), 20.);
+    assert_eq!(query(r###"percentile(d20, 0)"###), 1.);
+    assert_eq!(query(r###"min(3d6)"###), 3.);
+    assert_eq!(query(r###"max(4d6kh3 + 2)"###), 20.);
+
+    let x = dist(r###"P(d20 >= 11) + d2"###);
+    assert_eq!(x.len(), 2);
+    assert_close(prob(&x, 1), 0.);
+
+    assert!(analyze(&compile(parse(r###"P(d6, 2)"###).unwrap()).unwrap()).is_err());
+    assert!(compile(parse(r###"percentile(d6)"###).unwrap())
+        .unwrap()
+        .exec(&mut Context::default())
+        .is_err());
+    assert!(analyze(&compile(parse(r###"P(d6)"###).unwrap()).unwrap()).is_err());
+    assert!(analyze(&compile(parse(r###"percentile(d6, 101)"###).unwrap()).unwrap()).is_err());
+
+    assert_eq!(query(r###"min = (a) => a * 10; min(3)"###), 30.);
+    assert_eq!(query(r###"max = (a, b) => a; max(1, 2)"###), 1.);
+    assert_eq!(query(r###"f = (P) => P(d6 == 6); f((x) => 2)"###), 2.);
+    assert_eq!(query(r###"{ (E := (x) => 0); }; E(d6)"###), 3.5);
+
+    let limit = |input: &str, i: &mut Context| {
+        compile(parse(input).unwrap())
+            .unwrap()
+            .exec(i)
+            .map_err(|x| *x.data)
+    };
+    let mut i = Context::default();
+    for input in [
+        r###"P(100000d6 > 5)"###,
+        r###"E(100000d6)"###,
+        r###"Var(100000d6)"###,
+        r###"percentile(100000d6, 50)"###,
+        r###"max(100000d6)"###,
+    ] {
+        assert_eq!(limit(input, &mut i), Err(Error::DiceLimit), "{}", input);
+    }
+    i.max_dice = 3;
+    assert_eq!(limit(r###"E(4d6)"###, &mut i), Err(Error::DiceLimit));
+}
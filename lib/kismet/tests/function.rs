@@ -0,0 +1,150 @@
+use kismet::{
+    analyze, compile,
+    hir::{Context, Error, Exec, Primitive, Value},
+    parse,
+};
+
+mod util;
+use util::{error, exec, integer, list};
+
+#[test]
+fn call() {
+    assert_eq!(exec(r###"f = (x) => x + 1; f(1)"###), integer(2));
+    assert_eq!(exec(r###"f = x => x * 2; f(4)"###), integer(8));
+    assert_eq!(exec(r###"f = () => 3; f()"###), integer(3));
+    assert_eq!(exec(r###"f = (a, b) => a - b; f(5, 2)"###), integer(3));
+    assert_eq!(
+        exec(r###"f = (x) => { y = x * 2; y + 1 }; f(3)"###),
+        integer(7)
+    );
+    assert_eq!(exec(r###"f = (x) => (y) => x + y; f(1)(2)"###), integer(3));
+    assert_eq!(
+        exec(r###"f = (x) => {a: x}; f(1)"###),
+        exec(r###"{a: 1}"###)
+    );
+    assert_eq!(
+        exec(r###"attack = (bonus) => 1d20 + bonus; x = attack(5); 6 <= x <= 25"###),
+        Value::Primitive(Primitive::Boolean(true))
+    );
+    assert_eq!(
+        exec(r###"apply = (f, x) => f(x); apply((x) => x * 3, 2)"###),
+        integer(6)
+    );
+    assert_eq!(exec(r###"f = (x) => x; f"###).to_string(), "function");
+    assert_eq!(
+        exec(r###"f = (x) => x; P(d6 > 3)"###),
+        exec(r###"P(d6 > 3)"###)
+    );
+
+    assert_eq!(error(r###"x = 1; x(2)"###), Error::TypeMismatch);
+    assert_eq!(error(r###"f = (a, b) => a; f(1)"###), Error::TypeMismatch);
+    assert_eq!(error(r###"f = (a) => a; f(1, 2)"###), Error::TypeMismatch);
+}
+
+#[test]
+fn params() {
+    assert_eq!(exec(r###"f = (a, b = 2) => a + b; f(1)"###), integer(3));
+    assert_eq!(exec(r###"f = (a, b = 2) => a + b; f(1, 5)"###), integer(6));
+    assert_eq!(
+        exec(r###"f = (a, b = a * 10) => a + b; f(1)"###),
+        integer(11)
+    );
+    assert_eq!(
+        exec(r###"f = (a, ...rest) => rest; f(1, 2, 3)"###),
+        list(&[2, 3])
+    );
+    assert_eq!(exec(r###"f = (a, ...rest) => rest; f(1)"###), list(&[]));
+    assert_eq!(exec(r###"f = (...[a, b]) => b; f(1, 2)"###), integer(2));
+    assert_eq!(
+        exec(r###"f = ((a, b), {c}) => a + b + c; f((1, 2), {c: 3})"###),
+        integer(6)
+    );
+    assert_eq!(exec(r###"f = ([a, ...r]) => r; f([1, 2])"###), list(&[2]));
+
+    assert_eq!(
+        error(r###"f = ((a, b)) => a; f([1])"###),
+        Error::TypeMismatch
+    );
+    assert!(compile(parse(r###"f = (...a, b) => a"###).unwrap()).is_err());
+}
+
+#[test]
+fn closure() {
+    assert_eq!(exec(r###"x = 1; f = () => x; x = 2; f()"###), integer(1));
+    assert_eq!(
+        error(r###"x = 1; f = (y) => { x = y; x }; f(5); x"###),
+        Error::Captured
+    );
+    assert_eq!(
+        exec(r###"x = 1; f = (y) => { x := y; x }; f(5) * 10 + x"###),
+        integer(51)
+    );
+    assert_eq!(
+        exec(r###"f = (y) => { z = y }; f(5); z"###),
+        Value::default()
+    );
+    assert_eq!(
+        exec(
+            r###"adder = (n) => (x) => x + n; add2 = adder(2); add5 = adder(5); add2(1) + add5(1)"###
+        ),
+        integer(9)
+    );
+}
+
+#[test]
+fn recursion() {
+    assert_eq!(
+        exec(r###"fact = (n) => if n <= 1 { 1 } else { n * fact(n - 1) }; fact(5)"###),
+        integer(120)
+    );
+    assert_eq!(
+        exec(r###"fib = (n) => if n < 2 { n } else { fib(n - 1) + fib(n - 2) }; fib(10)"###),
+        integer(55)
+    );
+    assert_eq!(
+        exec(r###"f = (n) => if n { f(n - 1) } else { 0 }; g = f; f = 5; g(3)"###),
+        integer(0)
+    );
+    assert_eq!(exec(r###"f = (f) => f; f(2)"###), integer(2));
+    assert_eq!(
+        error(r###"f = (n) => f(n + 1); f(0)"###),
+        Error::RecursionLimit
+    );
+}
+
+#[test]
+fn return_stmt() {
+    assert_eq!(
+        exec(r###"f = (x) => { if x > 1 { return "big" }; "small" }; f(2)"###),
+        Value::Primitive(Primitive::String(String::from("big")))
+    );
+    assert_eq!(
+        exec(r###"f = (x) => { for y in 0..10 { if y == x { return y * 2 } }; 0 }; f(3)"###),
+        integer(6)
+    );
+    assert_eq!(
+        exec(r###"f = (x) => { return x }; n = 0; for y in 0..3 { n = n + f(y) }; n"###),
+        integer(3)
+    );
+    assert_eq!(error(r###"return 1"###), Error::Return(integer(1)));
+    assert_eq!(
+        error(r###"f = () => { break 1 }; loop { f() }"###),
+        Error::InvalidOp
+    );
+}
+
+#[test]
+fn limit() {
+    assert_eq!(error(r###"f = (g) => g(g); f(f)"###), Error::RecursionLimit);
+    let input =
+        compile(parse(r###"f = (g, n) => if n { g(g, n - 1) } else { 0 }; f(f, 10)"###).unwrap())
+            .unwrap();
+    let mut i = Context::default();
+    assert_eq!(input.exec(&mut i), Ok(integer(0)));
+    i.max_depth = 10;
+    assert_eq!(
+        input.exec(&mut i).map_err(|x| *x.data),
+        Err(Error::RecursionLimit)
+    );
+    assert!(analyze(&compile(parse(r###"f = (x) => x; f(d6)"###).unwrap()).unwrap()).is_err());
+}
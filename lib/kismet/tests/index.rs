@@ -0,0 +1,85 @@
+use kismet::hir::{Collection, Error, Primitive, Range, Value};
+
+mod util;
+use util::{error, exec, integer, list, string};
+
+#[test]
+fn subscription() {
+    assert_eq!(exec(r###"xs = [1, 2, 3]; xs[0]"###), integer(1));
+    assert_eq!(exec(r###"xs = [1, 2, 3]; xs[-1]"###), integer(3));
+    assert_eq!(exec(r###"xs = [1, 2, 3]; xs[-3]"###), integer(1));
+    assert_eq!(exec(r###"xs = (1, 2, 3); xs[1]"###), integer(2));
+    assert_eq!(exec(r###"xs = [1, 2, 3]; i = 1; xs[i + 1]"###), integer(3));
+    assert_eq!(exec(r###"[[1, 2], [3, 4]][1][0]"###), integer(3));
+    assert_eq!(exec(r###"m = [[1, 2], [3, 4]]; m[1, 0]"###), integer(3));
+    assert_eq!(exec(r###"x = {a: 1, b: 2}; x["b"]"###), integer(2));
+    assert_eq!(exec(r###"x = {a: [1, 2]}; x["a"][-1]"###), integer(2));
+    assert_eq!(exec(r###""abc"[1]"###), string("b"));
+    assert_eq!(exec(r###""héllo"[-4]"###), string("é"));
+    assert_eq!(exec(r###"(0..10)[2]"###), integer(2));
+    assert_eq!(exec(r###"(5..10)[-1]"###), integer(9));
+    assert_eq!(exec(r###"(5..=10)[-1]"###), integer(10));
+    assert_eq!(exec(r###"(5..)[1000000000]"###), integer(1000000005));
+
+    assert_eq!(error(r###"xs = [1, 2, 3]; xs[3]"###), Error::OutOfBounds);
+    assert_eq!(error(r###"xs = [1, 2, 3]; xs[-4]"###), Error::OutOfBounds);
+    assert_eq!(error(r###"x = ""; x[0]"###), Error::OutOfBounds);
+    assert_eq!(error(r###"x = 0..3; x[3]"###), Error::OutOfBounds);
+    assert_eq!(error(r###"x = 0..; x[-1]"###), Error::OutOfBounds);
+    assert_eq!(error(r###"x = {a: 1}; x["b"]"###), Error::MissingKey);
+    assert_eq!(error(r###"xs = [1]; xs["a"]"###), Error::TypeMismatch);
+    assert_eq!(error(r###"xs = [1]; xs[0.5]"###), Error::TypeMismatch);
+    assert_eq!(error(r###"x = 1; x[0]"###), Error::TypeMismatch);
+}
+
+#[test]
+fn slice() {
+    assert_eq!(exec(r###"xs = [1, 2, 3, 4]; xs[1..3]"###), list(&[2, 3]));
+    assert_eq!(exec(r###"xs = [1, 2, 3, 4]; xs[1..]"###), list(&[2, 3, 4]));
+    assert_eq!(exec(r###"xs = [1, 2, 3, 4]; xs[..=1]"###), list(&[1, 2]));
+    assert_eq!(exec(r###"xs = [1, 2, 3, 4]; xs[-2..]"###), list(&[3, 4]));
+    assert_eq!(exec(r###"xs = [1, 2, 3, 4]; xs[..-1]"###), list(&[1, 2, 3]));
+    assert_eq!(exec(r###"xs = [1, 2, 3, 4]; xs[3..1]"###), list(&[]));
+    assert_eq!(exec(r###"xs = [1, 2, 3, 4]; xs[2..10]"###), list(&[3, 4]));
+    assert_eq!(
+        exec(r###"xs = (1, 2, 3); xs[1..]"###),
+        Value::Collection(Collection::Tuple(vec![integer(2), integer(3)]))
+    );
+    assert_eq!(exec(r###""hello"[1..=3]"###), string("ell"));
+    assert_eq!(exec(r###""hello"[-3..]"###), string("llo"));
+    assert_eq!(
+        exec(r###"(10..20)[2..-2]"###),
+        Value::Range(Range {
+            start: Some(12),
+            end: Some(18),
+            inclusive: false,
+        })
+    );
+    assert_eq!(error(r###"x = 0..; x[1..2]"###), Error::InvalidOp);
+}
+
+#[test]
+fn attribute() {
+    assert_eq!(exec(r###"x = {str: 12, dex: 14}; x.dex"###), integer(14));
+    assert_eq!(exec(r###"x = {a: {b: 3}}; x.a.b"###), integer(3));
+    assert_eq!(exec(r###"x = {a: [4, 5]}; x.a[1]"###), integer(5));
+    assert_eq!(exec(r###"t = (1, 2); t.0"###), integer(1));
+    assert_eq!(exec(r###"t = (1, (2, 3)); t.1.0"###), integer(2));
+    assert_eq!(
+        exec(r###"t = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]; t.10"###),
+        integer(10)
+    );
+    assert_eq!(exec(r###"f = () => {a: 1}; f().a"###), integer(1));
+
+    assert_eq!(error(r###"x = {a: 1}; x.b"###), Error::MissingKey);
+    assert_eq!(error(r###"t = (1, 2); t.2"###), Error::OutOfBounds);
+    assert_eq!(error(r###"t = (1, 2); t.a"###), Error::TypeMismatch);
+}
+
+#[test]
+fn fraction() {
+    assert_eq!(exec(r###".5"###), Value::Primitive(Primitive::Float(0.5)));
+    assert_eq!(exec(r###".25"###), Value::Primitive(Primitive::Float(0.25)));
+    assert_eq!(exec(r###".10"###), Value::Primitive(Primitive::Float(0.1)));
+    assert_eq!(exec(r###".0"###), Value::Primitive(Primitive::Float(0.)));
+}
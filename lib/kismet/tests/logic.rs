@@ -0,0 +1,77 @@
+use kismet::{
+    analyze, compile,
+    hir::{Primitive, Value},
+    parse,
+};
+
+mod util;
+use util::{boolean, exec, integer};
+
+#[test]
+fn truthiness() {
+    assert_eq!(exec(r###"not 0"###), boolean(true));
+    assert_eq!(exec(r###"not 1"###), boolean(false));
+    assert_eq!(exec(r###"not 0.0"###), boolean(true));
+    assert_eq!(exec(r###"not 0.5"###), boolean(false));
+    assert_eq!(exec(r###"not """###), boolean(true));
+    assert_eq!(exec(r###"not "a""###), boolean(false));
+    assert_eq!(exec(r###"not undefined"###), boolean(true));
+    assert_eq!(exec(r###"not []"###), boolean(true));
+    assert_eq!(exec(r###"not [0]"###), boolean(false));
+    assert_eq!(exec(r###"not ()"###), boolean(true));
+    assert_eq!(exec(r###"not {}"###), boolean(true));
+    assert_eq!(exec(r###"not {a: 1}"###), boolean(false));
+    assert_eq!(exec(r###"not 1 < 2"###), boolean(false));
+    assert_eq!(exec(r###"not 2d1 - 2"###), boolean(true));
+}
+
+#[test]
+fn and_or() {
+    assert_eq!(exec(r###"1 and 2"###), integer(2));
+    assert_eq!(exec(r###"0 and 2"###), integer(0));
+    assert_eq!(exec(r###"1 or 2"###), integer(1));
+    assert_eq!(exec(r###"0 or 2"###), integer(2));
+    assert_eq!(exec(r###"0 or "" or 3"###), integer(3));
+    assert_eq!(exec(r###"1 and 2 and 0 and 3"###), integer(0));
+    assert_eq!(exec(r###"0 or 1 and 2"###), integer(2));
+    assert_eq!(
+        exec(r###"roll = 12; fumbled = 0; roll >= 10 and not fumbled"###),
+        boolean(true)
+    );
+    assert_eq!(
+        exec(r###"roll = 12; fumbled = 1; roll >= 10 and not fumbled"###),
+        boolean(false)
+    );
+    assert_eq!(exec(r###"x = 3; x > 1 or y"###), boolean(true));
+}
+
+#[test]
+fn short_circuit() {
+    assert_eq!(exec(r###"x = 0; x and (y := 5); y"###), Value::default());
+    assert_eq!(exec(r###"x = 1; x and (y := 5); y"###), integer(5));
+    assert_eq!(exec(r###"x = 1; x or (y := 5); y"###), Value::default());
+    assert_eq!(exec(r###"x = 0; x or (y := 5); y"###), integer(5));
+    assert_eq!(exec(r###"x = 0; s = "a"; x and s - 1"###), integer(0));
+}
+
+#[test]
+fn distribution() {
+    let x = analyze(&compile(parse(r###"d6 >= 4 and d6 >= 4"###).unwrap()).unwrap()).unwrap();
+    let p = x
+        .iter()
+        .find(|x| x.0 == Primitive::Boolean(true))
+        .unwrap()
+        .1;
+    assert!((p - 0.25).abs() < 1e-9);
+
+    let x = analyze(&compile(parse(r###"P(d20 == 20 or d20 == 20)"###).unwrap()).unwrap()).unwrap();
+    assert_eq!(x.single(), Some(&Primitive::Float(0.0975)));
+
+    let x = analyze(&compile(parse(r###"d2 - 1 or 5"###).unwrap()).unwrap()).unwrap();
+    assert_eq!(
+        x.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
+        vec![Primitive::Integer(1), Primitive::Integer(5)]
+    );
+    let x = analyze(&compile(parse(r###"not d2 - 1"###).unwrap()).unwrap()).unwrap();
+    assert_eq!(x.len(), 2);
+}
@@ -0,0 +1,129 @@
+use kismet::{
+    analyze, compile,
+    hir::{Collection, Context, Error, Exec, Primitive, Value},
+    parse,
+};
+
+mod util;
+use util::{error, exec, integer};
+
+#[test]
+fn for_loop() {
+    assert_eq!(
+        exec(r###"n = 0; for x in 1..=4 { n = n + x }; n"###),
+        integer(10)
+    );
+    assert_eq!(
+        exec(r###"n = 0; for x in [3, 4] { n = n * 10 + x }; n"###),
+        integer(34)
+    );
+    assert_eq!(exec(r###"n = 0; for x in (5,) { n = x }; n"###), integer(5));
+    assert_eq!(exec(r###"for x in 0..3 { x }"###), Value::default());
+    assert_eq!(
+        exec(r###"for x in (0..) { if x * x > 50 { break x } }"###),
+        integer(8)
+    );
+    assert_eq!(
+        exec(r###"n = 0; for (a, b) in [(1, 2), (3, 4)] { n = n + a * b }; n"###),
+        integer(14)
+    );
+    assert_eq!(
+        exec(r###"n = []; for [a, ...r] in [[1, 2, 3], [4]] { n = r }; n"###),
+        Value::Collection(Collection::List(vec![]))
+    );
+    assert_eq!(
+        exec(r###"for (k, v) in {a: 1, b: 2} { if v == 2 { break k } }"###),
+        Value::Primitive(Primitive::String(String::from("b")))
+    );
+    assert_eq!(
+        exec(r###"n = 0; for {a} in [{a: 1}, {a: 2, b: 3}] { n = n + a }; n"###),
+        integer(3)
+    );
+
+    assert_eq!(error(r###"for x in 1 { x }"###), Error::TypeMismatch);
+    assert_eq!(error(r###"for (a, b) in [1] { a }"###), Error::TypeMismatch);
+}
+
+#[test]
+fn while_loop() {
+    assert_eq!(
+        exec(r###"i = 0; while i < 10 { i = i + 1 }; i"###),
+        integer(10)
+    );
+    assert_eq!(
+        exec(r###"i = 0; while i < 10 { i = i + 1 }"###),
+        Value::default()
+    );
+    assert_eq!(
+        exec(r###"i = 0; while 1 { i = i + 2; if i > 7 { break i } }"###),
+        integer(8)
+    );
+    assert_eq!(
+        exec(r###"i = 5; while i < 3 { break 1 }"###),
+        Value::default()
+    );
+}
+
+#[test]
+fn loop_break() {
+    assert_eq!(
+        exec(r###"n = 0; loop { n = n + 1; if n > 5 { break n * 2 } }"###),
+        integer(12)
+    );
+    assert_eq!(
+        exec(
+            r###":outer: for x in 0..3 { for y in 0..3 { if x * y == 2 { break :outer: (x, y) } } }"###
+        ),
+        Value::Collection(Collection::Tuple(vec![integer(1), integer(2)]))
+    );
+    assert_eq!(
+        exec(r###"n = 0; for x in 0..3 { m = :inner: loop { break :inner: x }; n = n + m }; n"###),
+        integer(3)
+    );
+    assert_eq!(
+        exec(r###"n = 0; for x in 0..3 { loop { break 1 }; n = n + 1 }; n"###),
+        integer(3)
+    );
+    assert_eq!(error(r###"break 1"###), Error::Break(None, integer(1)));
+}
+
+#[test]
+fn limit() {
+    assert_eq!(error(r###"loop { 1 }"###), Error::IterationLimit);
+    assert_eq!(error(r###"for x in (0..) { x }"###), Error::IterationLimit);
+
+    let input = compile(parse(r###"n = 0; while n < 100 { n = n + 1 }; n"###).unwrap()).unwrap();
+    let mut i = Context::default();
+    i.max_iter = 100;
+    assert_eq!(input.exec(&mut i), Ok(integer(100)));
+    i.max_iter = 99;
+    assert_eq!(
+        input.exec(&mut i).map_err(|x| *x.data),
+        Err(Error::IterationLimit)
+    );
+
+    let input =
+        compile(parse(r###"n = 0; for x in 0..10 { for y in 0..10 { n = n + 1 } }; n"###).unwrap())
+            .unwrap();
+    i.max_iter = 110;
+    assert_eq!(input.exec(&mut i), Ok(integer(100)));
+    i.max_iter = 109;
+    assert_eq!(
+        input.exec(&mut i).map_err(|x| *x.data),
+        Err(Error::IterationLimit)
+    );
+    assert_eq!(
+        error(r###"for x in 0..1000 { for y in 0..1000 { y } }"###),
+        Error::IterationLimit
+    );
+    assert_eq!(
+        error(r###"f = () => for y in 0..200 { y }; for x in 0..100 { f() }"###),
+        Error::IterationLimit
+    );
+    assert_eq!(
+        error(r###"for x in 0..200 { [y for y in 0..100] }"###),
+        Error::IterationLimit
+    );
+
+    assert!(analyze(&compile(parse(r###"loop { break d6 }"###).unwrap()).unwrap()).is_err());
+}
@@ -0,0 +1,125 @@
+use kismet::{
+    compile,
+    hir::{Context, Error, Exec, Primitive, Range, Value},
+    parse,
+};
+
+mod util;
+use util::{error, exec, list};
+
+fn range(start: Option<i32>, end: Option<i32>, inclusive: bool) -> Value {
+    Value::Range(Range {
+        start,
+        end,
+        inclusive,
+    })
+}
+
+#[test]
+fn values() {
+    assert_eq!(exec(r###"0..5"###), range(Some(0), Some(5), false));
+    assert_eq!(exec(r###"0..=5"###), range(Some(0), Some(5), true));
+    assert_eq!(exec(r###"2.."###), range(Some(2), None, false));
+    assert_eq!(exec(r###"..5"###), range(None, Some(5), false));
+    assert_eq!(exec(r###"..=5"###), range(None, Some(5), true));
+    assert_eq!(exec(r###".."###), range(None, None, false));
+    assert_eq!(exec(r###"-2..2"###), range(Some(-2), Some(2), false));
+    assert_eq!(
+        exec(r###"x = 2; x..x * 3"###),
+        range(Some(2), Some(6), false)
+    );
+    assert_eq!(exec(r###"1..3d1"###), range(Some(1), Some(3), false));
+    assert_eq!(exec(r###"1..=3"###).to_string(), "1..=3");
+    assert_eq!(exec(r###"..5"###).to_string(), "..5");
+    assert_eq!(
+        exec(r###"0..3 == 0..3"###),
+        Value::Primitive(Primitive::Boolean(true))
+    );
+    assert_eq!(
+        exec(r###"not 3..3"###),
+        Value::Primitive(Primitive::Boolean(true))
+    );
+    assert_eq!(
+        exec(r###"not 3..=3"###),
+        Value::Primitive(Primitive::Boolean(false))
+    );
+
+    assert!(compile(parse(r###""a"..3"###).unwrap()).is_err());
+    let x = compile(parse(r###"x = 1.5; 0..x"###).unwrap())
+        .unwrap()
+        .exec(&mut Context::default());
+    assert_eq!(x.map_err(|x| *x.data), Err(Error::TypeMismatch));
+}
+
+#[test]
+fn spread() {
+    assert_eq!(exec(r###"[...0..5]"###), list(&[0, 1, 2, 3, 4]));
+    assert_eq!(exec(r###"[...(1..=3), 9]"###), list(&[1, 2, 3, 9]));
+    assert_eq!(exec(r###"x = 3; [...x..=x + 2]"###), list(&[3, 4, 5]));
+    assert_eq!(exec(r###"[...5..0]"###), list(&[]));
+    let x = compile(parse(r###"[...0..]"###).unwrap())
+        .unwrap()
+        .exec(&mut Context::default());
+    assert_eq!(x.map_err(|x| *x.data), Err(Error::InvalidOp));
+    assert_eq!(error(r###"[...0..2000000000]"###), Error::IterationLimit);
+
+    let node = compile(parse(r###"[...0..3]"###).unwrap()).unwrap();
+    let mut i = Context::default();
+    i.max_iter = 3;
+    assert!(node.exec(&mut i).is_ok());
+    i.max_iter = 2;
+    assert_eq!(
+        node.exec(&mut i).map_err(|x| *x.data),
+        Err(Error::IterationLimit)
+    );
+}
+
+#[test]
+fn iterate() {
+    let x = Range {
+        start: Some(1),
+        end: None,
+        inclusive: false,
+    };
+    assert_eq!(x.len(), None);
+    assert_eq!(x.iter().take(3).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert!(x.contains(1_000_000) && !x.contains(0));
+    let x = Range {
+        start: Some(i32::MAX - 1),
+        end: None,
+        inclusive: false,
+    };
+    assert_eq!(x.iter().count(), 2);
+
+    let x = Range {
+        start: None,
+        end: Some(3),
+        inclusive: true,
+    };
+    assert_eq!(x.len(), Some(4));
+    assert_eq!(x.iter().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+    let mut x = exec(r###"0..1000000000"###).iterate().unwrap();
+    assert_eq!(x.nth(10), Some(Value::Primitive(Primitive::Integer(10))));
+    assert_eq!(exec(r###"[1, 2]"###).iterate().unwrap().count(), 2);
+    assert!(exec(r###"1"###).iterate().is_err());
+}
+
+#[test]
+fn resolve() {
+    let range = |start, end, inclusive| Range {
+        start,
+        end,
+        inclusive,
+    };
+    assert_eq!(range(Some(1), Some(3), false).resolve(5), 1..3);
+    assert_eq!(range(Some(1), Some(3), true).resolve(5), 1..4);
+    assert_eq!(range(None, None, false).resolve(5), 0..5);
+    assert_eq!(range(Some(-2), None, false).resolve(5), 3..5);
+    assert_eq!(range(None, Some(-1), false).resolve(5), 0..4);
+    assert_eq!(range(None, Some(-1), true).resolve(5), 0..5);
+    assert_eq!(range(None, Some(-6), true).resolve(5), 0..0);
+    assert_eq!(range(Some(3), Some(1), false).resolve(5), 3..3);
+    assert_eq!(range(Some(2), Some(10), false).resolve(5), 2..5);
+    assert_eq!(range(Some(-10), Some(2), false).resolve(5), 0..2);
+}
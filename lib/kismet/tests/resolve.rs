@@ -0,0 +1,150 @@
+use kismet::{
+    analyze, compile,
+    hir::{Context, Error, Exec, Instruction, Value},
+    parse, resolve,
+};
+
+mod util;
+use util::integer;
+
+fn exec(input: &str) -> Value {
+    let node = compile(parse(input).unwrap()).unwrap();
+    let val = node.exec(&mut Context::seeded(7)).unwrap();
+    assert_eq!(resolve(&node).exec(&mut Context::seeded(7)).unwrap(), val);
+    val
+}
+
+fn error(input: &str) -> Error {
+    let node = compile(parse(input).unwrap()).unwrap();
+    let x = resolve(&node).exec(&mut Context::seeded(7));
+    *x.unwrap_err().data
+}
+
+#[test]
+fn slots() {
+    let node = resolve(&compile(parse(r###"x = 1; y = x + z; y"###).unwrap()).unwrap());
+    assert!(matches!(*node.data.0[0].data, Instruction::Store(0, _)));
+    assert_eq!(*node.data.0[2].data, Instruction::Local(1));
+    let Instruction::Store(1, x) = &*node.data.0[1].data else {
+        panic!()
+    };
+    assert!(matches!(
+        &*x.data,
+        Instruction::Arith(lhs, _, rhs)
+            if *lhs.data == Instruction::Local(0)
+                && matches!(&*rhs.data, Instruction::Variable(_))
+    ));
+}
+
+#[test]
+fn scope() {
+    assert_eq!(exec(r###"x = 1; { x = 2; }; x"###), integer(2));
+    assert_eq!(exec(r###"x = 1; { (x := 2); }; x"###), integer(1));
+    assert_eq!(exec(r###"x = 1; { (x := 2); x = 3 }; x"###), integer(1));
+    assert_eq!(exec(r###"{ y = 2; }; y"###), Value::default());
+    assert_eq!(exec(r###"x = 1; if x { x = 5 }; x"###), integer(5));
+    assert_eq!(exec(r###"if 1 { y = 4 }; y"###), Value::default());
+    assert_eq!(
+        exec(r###"x = 1; match (2, 3) { (x, y) => x + y }; x"###),
+        integer(1)
+    );
+    assert_eq!(
+        exec(r###"(a, b) = (1, 2); (a, b) = (b, a); a"###),
+        integer(2)
+    );
+    assert_eq!(
+        exec(r###"{a, b} = {a: 1, b: 2}; {a, b}"###),
+        exec(r###"{a: 1, b: 2}"###)
+    );
+}
+
+#[test]
+fn loops() {
+    assert_eq!(
+        exec(r###"n = 0; for x in 0..4 { n = n + x }; n"###),
+        integer(6)
+    );
+    assert_eq!(exec(r###"x = 7; for x in 0..3 { x }; x"###), integer(7));
+    assert_eq!(
+        exec(r###"n = 0; while n < 5 { m = n; n = m + 1 }; m"###),
+        Value::default()
+    );
+    assert_eq!(
+        exec(r###"n = 0; loop { n = n + 1d6; if n > 20 { break n } }"###),
+        exec(r###"n = 0; loop { n = n + 1d6; if n > 20 { break n } }"###)
+    );
+}
+
+#[test]
+fn closure() {
+    assert_eq!(exec(r###"x = 1; f = () => x; x = 2; f()"###), integer(1));
+    assert_eq!(
+        exec(r###"x = 1; f = (y) => { x := y; x }; f(5) * 10 + x"###),
+        integer(51)
+    );
+    assert_eq!(
+        error(r###"x = 1; f = (y) => { x = y; x }; f(5)"###),
+        Error::Captured
+    );
+    assert_eq!(
+        exec(r###"adder = (n) => (x) => x + n; add2 = adder(2); add2(1) + adder(5)(1)"###),
+        integer(9)
+    );
+    assert_eq!(
+        exec(
+            r###"k = 10; f = (a, b = a + k, ...rest) => [b, ...rest]; [...f(1, 2, 3), ...f(1)]"###
+        ),
+        exec(r###"[2, 3, 11]"###)
+    );
+    assert_eq!(
+        exec(r###"f = (g, n) => if n { n + g(g, n - 1) } else { 0 }; f(f, 4)"###),
+        integer(10)
+    );
+    assert_eq!(error(r###"f = (g) => g(g); f(f)"###), Error::RecursionLimit);
+    assert_eq!(
+        exec(r###"fact = (n) => if n <= 1 { 1 } else { n * fact(n - 1) }; fact(5)"###),
+        integer(120)
+    );
+    assert_eq!(
+        error(r###"f = (n) => f(n + 1); f(0)"###),
+        Error::RecursionLimit
+    );
+}
+
+#[test]
+fn comprehension() {
+    assert_eq!(
+        exec(r###"k = 3; [x * k for x in 0..4 if x != 2]"###),
+        exec(r###"[0, 3, 9]"###)
+    );
+    assert_eq!(
+        exec(r###"n = 0; [{ n = n + x; n } for x in 0..3]"###),
+        exec(r###"[0, 1, 3]"###)
+    );
+    assert_eq!(
+        exec(r###"n = 0; [{ n = n + x; n } for x in 0..3]; n"###),
+        integer(0)
+    );
+    assert_eq!(
+        exec(r###"n = 0; g = (n + x for x in 0..2); n = 1; [...g]"###),
+        exec(r###"[0, 1]"###)
+    );
+    assert_eq!(
+        exec(r###"party = ["a", "b"]; {[name]: 1d20 for name in party}"###),
+        exec(r###"party = ["a", "b"]; {[name]: 1d20 for name in party}"###)
+    );
+    assert_eq!(
+        exec(r###"[(a, b) for a in 0..3 for b in (x for x in 0..a)]"###),
+        exec(r###"[(1, 0), (2, 0), (2, 1)]"###)
+    );
+}
+
+#[test]
+fn query() {
+    assert_eq!(exec(r###"x = 3; P(d6 > x)"###), exec(r###"P(d6 > 3)"###));
+    assert_eq!(exec(r###"min = (a) => a * 10; min(3)"###), integer(30));
+    assert!(analyze(&resolve(
+        &compile(parse(r###"x = 2d6; x"###).unwrap()).unwrap()
+    ))
+    .is_ok());
+}
@@ -0,0 +1,87 @@
+use kismet::{
+    compile,
+    hir::{Context, Error, Exec, Value},
+    parse,
+};
+
+mod util;
+use util::{error, exec, integer};
+
+#[test]
+fn block_scope() {
+    assert_eq!(exec(r###"x = 1; { x = 2; }; x"###), integer(2));
+    assert_eq!(exec(r###"x = 1; { x = 2 }; x"###), integer(2));
+    assert_eq!(exec(r###"x = 1; { x := 2 }; x"###), integer(1));
+    assert_eq!(exec(r###"x = 1; { x := 2 }"###), integer(2));
+    assert_eq!(exec(r###"x = 1; { (x := 2); }; x"###), integer(1));
+    assert_eq!(exec(r###"x = 1; { (x := 2); x }"###), integer(2));
+    assert_eq!(exec(r###"x = 1; { (x := 2); x = 3 }; x"###), integer(1));
+    assert_eq!(exec(r###"{ y = 2; }; y"###), Value::default());
+    assert_eq!(exec(r###"x = 1; if x { x = 5 }; x"###), integer(5));
+    assert_eq!(exec(r###"x = 1; if x { (x := 5) }; x"###), integer(1));
+    assert_eq!(exec(r###"if 1 { y = 4 }; y"###), Value::default());
+    assert_eq!(
+        exec(r###"x = 1; match (2, 3) { (x, y) => x + y }; x"###),
+        integer(1)
+    );
+}
+
+#[test]
+fn loop_scope() {
+    assert_eq!(
+        exec(r###"n = 0; for x in 0..3 { (n := x) }; n"###),
+        integer(0)
+    );
+    assert_eq!(exec(r###"x = 7; for x in 0..3 { x }; x"###), integer(7));
+    assert_eq!(
+        exec(r###"n = 0; for x in 0..3 { m = x; n = n + m }; m"###),
+        Value::default()
+    );
+    assert_eq!(
+        exec(r###"x = 7; [x for x in 0..3]; (x for x in 0..3); x"###),
+        integer(7)
+    );
+}
+
+#[test]
+fn function_scope() {
+    assert_eq!(
+        exec(r###"x = 1; f = (x) => { x = x + 1; x }; f(5) + x"###),
+        integer(7)
+    );
+    assert_eq!(
+        exec(r###"x = 1; f = () => { (x := 2); x }; f() * 10 + x"###),
+        integer(21)
+    );
+    assert_eq!(
+        error(r###"n = 0; inc = () => { n = n + 1; n }; inc()"###),
+        Error::Captured
+    );
+    assert_eq!(
+        exec(r###"inc = () => { n = 0; n = n + 1; n }; inc() + inc()"###),
+        integer(2)
+    );
+}
+
+#[test]
+fn declare_stmt() {
+    assert_eq!(exec(r###"x := 3"###), integer(3));
+    assert_eq!(exec(r###"x := 3; x"###), integer(3));
+    assert_eq!(exec(r###"x = 1; { x := 2; x := x + 1; x }"###), integer(3));
+}
+
+#[test]
+fn session() {
+    let mut i = Context::default();
+    let mut run = |input: &str| {
+        compile(parse(input).unwrap())
+            .unwrap()
+            .exec(&mut i)
+            .unwrap()
+    };
+    run(r###"hp = 10"###);
+    run(r###"{ (hp := 3); hp }"###);
+    assert_eq!(run(r###"hp"###), integer(10));
+    run(r###"{ hp = hp - 4; }"###);
+    assert_eq!(run(r###"hp"###), integer(6));
+}
@@ -0,0 +1,66 @@
+use kismet::{
+    analyze, compile,
+    hir::{Block, Estimate, Primitive, Simulation},
+    parse, simulate,
+    types::Node,
+};
+
+const Z: f64 = 3.;
+
+fn block(input: &str) -> Node<Block> {
+    compile(parse(input).unwrap()).unwrap()
+}
+
+fn sim(input: &str, seed: u64, threads: usize) -> Estimate {
+    Simulation {
+        samples: 10_000,
+        seed,
+        threads,
+        ..Simulation::default()
+    }
+    .run(&block(input))
+    .unwrap()
+}
+
+#[test]
+fn estimate() {
+    let x = simulate(&block(r###"2d6"###), 20_000, 0).unwrap();
+    assert_eq!(x.samples, 20_000);
+    assert!((x.dist.iter().map(|x| x.1).sum::<f64>() - 1.).abs() < 1e-9);
+    assert_eq!(x.prob(&Primitive::Integer(13)), 0.);
+    let (lo, hi) = x.interval(&Primitive::Integer(7), Z);
+    assert!(lo < 6. / 36. && 6. / 36. < hi, "{} {}", lo, hi);
+    let (lo, hi) = x.mean_interval(Z).unwrap();
+    assert!(lo < 7. && 7. < hi, "{} {}", lo, hi);
+
+    let mean = analyze(&block(r###"3d6!"###)).unwrap().mean().unwrap();
+    let (lo, hi) = sim(r###"3d6!"###, 1, 4).mean_interval(Z).unwrap();
+    assert!(lo < mean && mean < hi, "{} {} {}", lo, mean, hi);
+}
+
+#[test]
+fn streams() {
+    let x = sim(r###"3d6! + d20"###, 1, 1);
+    assert_eq!(x, sim(r###"3d6! + d20"###, 1, 3));
+    assert_eq!(x, sim(r###"3d6! + d20"###, 1, 64));
+    assert_ne!(x, sim(r###"3d6! + d20"###, 2, 1));
+}
+
+#[test]
+fn fresh_samples() {
+    let x = sim(r###"(1d2 == 1) and (x := 5); x"###, 1, 4);
+    for val in [Primitive::Undefined, Primitive::Integer(5)] {
+        let (lo, hi) = x.interval(&val, Z);
+        assert!(lo < 0.5 && 0.5 < hi, "{} {} {}", val, lo, hi);
+    }
+}
+
+#[test]
+fn errors() {
+    let x = Simulation {
+        samples: 10,
+        ..Simulation::default()
+    };
+    assert!(x.run(&block(r###"d["a", "b"]"###)).is_err());
+    assert!(x.run(&block(r###"d0"###)).is_err());
+}
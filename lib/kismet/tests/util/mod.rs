@@ -2,6 +2,8 @@ use std::ops::Range;
 
 use kismet::{
     ast::*,
+    compile,
+    hir::{Collection, Context, Error, Exec, Primitive, Value},
     parser::parse,
     types::{Integer, Node, Span},
 };
@@ -48,3 +50,47 @@ pub fn new_string(range: Range<usize>, val: &str) -> Node<Expr> {
 pub fn new_id(range: Range<usize>, val: &str) -> Node<Expr> {
     new_atom(range, Atom::Id(String::from(val)))
 }
+
+#[allow(dead_code)]
+pub fn new_die(range: Range<usize>, val: Node<Atom>, mods: Vec<Node<DieMod>>) -> Node<Expr> {
+    Node::new(range, Expr::Op(Op::Die(val, mods)))
+}
+
+#[allow(dead_code)]
+pub fn new_coefficient(lhs: Node<Atom>, rhs: Node<Expr>) -> Node<Expr> {
+    Node::new(lhs.span + rhs.span, Expr::Op(Op::Coefficient(lhs, rhs)))
+}
+
+#[allow(dead_code)]
+pub fn exec(input: &str) -> Value {
+    let node = compile(parse(input).unwrap()).unwrap();
+    node.exec(&mut Context::default()).unwrap()
+}
+
+#[allow(dead_code)]
+pub fn error(input: &str) -> Error {
+    let x = compile(parse(input).unwrap())
+        .unwrap()
+        .exec(&mut Context::default());
+    *x.unwrap_err().data
+}
+
+#[allow(dead_code)]
+pub fn integer(val: i32) -> Value {
+    Value::Primitive(Primitive::Integer(val))
+}
+
+#[allow(dead_code)]
+pub fn boolean(val: bool) -> Value {
+    Value::Primitive(Primitive::Boolean(val))
+}
+
+#[allow(dead_code)]
+pub fn string(val: &str) -> Value {
+    Value::Primitive(Primitive::String(String::from(val)))
+}
+
+#[allow(dead_code)]
+pub fn list(val: &[i32]) -> Value {
+    Value::Collection(Collection::List(val.iter().map(|x| integer(*x)).collect()))
+}